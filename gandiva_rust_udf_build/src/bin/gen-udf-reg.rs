@@ -18,5 +18,5 @@ fn main() {
     let path_str = args.get(1).unwrap();
     println!("generating in given dir: {}", path_str);
     let path = Path::new(path_str);
-    generate_udf_registry(&path);
+    generate_udf_registry(path);
 }
\ No newline at end of file
@@ -2,12 +2,20 @@ extern crate gandiva_rust_udf_shared;
 extern crate gandiva_rust_udf_macro;
 
 use std::collections::HashMap;
-use gandiva_rust_udf_common::map_type;
-use proc_macro2::TokenStream;
+use gandiva_rust_udf_common::{
+    is_context_type, is_string_writer_type, is_supported_param_type, is_supported_return_type,
+    signature_type_name, strip_lifetimes, strip_result_type, try_map_type, wrapper_function_name,
+    SUPPORTED_PARAM_TYPES, SUPPORTED_RETURN_TYPES,
+};
+use proc_macro2::{Ident, TokenStream, TokenTree};
 use quote::{format_ident, quote};
 use std::fs;
 use std::path::Path;
-use syn::{visit::Visit, File, FnArg, ItemFn};
+use syn::parse::Parse;
+use syn::visit_mut::VisitMut;
+use syn::{
+    visit::Visit, Attribute, Expr, File, FnArg, ItemFn, LitBool, Meta, ReturnType, Signature, Type, TypePath,
+};
 use toml::Value;
 use walkdir::WalkDir;
 use strfmt::strfmt;
@@ -20,24 +28,18 @@ impl<'ast> Visit<'ast> for UdfCollector {
     fn visit_item_fn(&mut self, i: &'ast ItemFn) {
         for attr in &i.attrs {
             if attr.path().is_ident("udf") {
-                let fn_name = i.sig.ident.to_string();
-                let mut arg_types = Vec::new();
-                for input in &i.sig.inputs {
-                    match input {
-                        FnArg::Typed(pat_type) => {
-                            let ty = &pat_type.ty;
-                            let arg_type = quote!(#ty).to_string();
-                            let arg_type_str = arg_type.as_str();
-                            let mapped_gdv_arg_type = map_type(arg_type_str);
-                            arg_types.push(mapped_gdv_arg_type);
-                        }
-                        _ => {
-                            panic!("Unsupported function argument type");
-                        }
+                let udf_attr = _parse_udf_attr(attr);
+                // generic functions have a wrapper for each instantiation, the same as the `udf` macro
+                for instantiation in _type_instantiations(&i.sig, &udf_attr.types) {
+                    let mut sig = i.sig.clone();
+                    let mut substitution = TypeSubstitution(&instantiation);
+                    for input in sig.inputs.iter_mut() {
+                        substitution.visit_fn_arg_mut(input);
                     }
+                    substitution.visit_return_type_mut(&mut sig.output);
+                    let wrapper_name = _wrapper_name(&sig, &udf_attr);
+                    self.udf_registration_funcs.push(wrapper_name);
                 }
-                let wrapper_name = format_ident!("{}_{}", fn_name, arg_types.join("_"));
-                self.udf_registration_funcs.push(wrapper_name.to_string());
                 break;
             }
         }
@@ -45,14 +47,162 @@ impl<'ast> Visit<'ast> for UdfCollector {
     }
 }
 
-fn _get_package_dir_name(path: &Path) -> Option<String> {
-    if let Some(dir_name) = path.file_name() {
-        Some(dir_name.to_string_lossy().into_owned())
-    } else {
-        None
+// return the name of the wrapper function generated by the `udf` macro for the (instantiated) signature
+fn _wrapper_name(sig: &Signature, udf_attr: &UdfAttr) -> String {
+    let fn_name = sig.ident.to_string();
+    // the same types as the `udf` macro are accepted, see `validate_types` of the macro
+    let is_custom_type = |type_str: &str| udf_attr.unchecked_types && try_map_type(type_str).is_none();
+    // the holder parameter is a reference to the holder type, e.g. `&RegexHolder`
+    let holder_arg_type = udf_attr.holder.as_ref().map(|holder| {
+        let holder_type: String = quote!(#holder).to_string().split_whitespace().collect();
+        format!("&{}", holder_type)
+    });
+    let mut arg_types = Vec::new();
+    let mut writes_string = false;
+    for input in &sig.inputs {
+        match input {
+            FnArg::Typed(pat_type) => {
+                let ty = &pat_type.ty;
+                let arg_type = quote!(#ty).to_string();
+                let arg_type_str = arg_type.as_str();
+                // the gandiva context, the holder and the string writer are not parameters of the gandiva function
+                if is_context_type(arg_type_str) {
+                    continue;
+                }
+                let compact_arg_type: String = strip_lifetimes(arg_type_str).split_whitespace().collect();
+                if holder_arg_type.as_ref() == Some(&compact_arg_type) {
+                    continue;
+                }
+                if is_string_writer_type(arg_type_str) {
+                    writes_string = true;
+                    continue;
+                }
+                if !is_supported_param_type(arg_type_str) && !is_custom_type(arg_type_str) {
+                    panic!(
+                        "Unsupported parameter type `{}` of UDF `{}`, supported types are {}",
+                        arg_type_str, fn_name, SUPPORTED_PARAM_TYPES
+                    );
+                }
+                let mapped_gdv_arg_type = signature_type_name(arg_type_str);
+                arg_types.push(mapped_gdv_arg_type);
+            }
+            _ => {
+                panic!("Unsupported function argument type");
+            }
+        }
+    }
+    // the string result is written into `GdvStringWriter` instead of being returned
+    if writes_string {
+        return wrapper_function_name(&fn_name, &arg_types);
+    }
+    if let ReturnType::Type(_, ty) = &sig.output {
+        let return_type = quote!(#ty).to_string();
+        let return_type_str = strip_result_type(&return_type).unwrap_or(&return_type);
+        if !is_supported_return_type(return_type_str) && !is_custom_type(return_type_str) {
+            panic!(
+                "Unsupported return type `{}` of UDF `{}`, supported types are {}",
+                return_type_str, fn_name, SUPPORTED_RETURN_TYPES
+            );
+        }
+    }
+    wrapper_function_name(&fn_name, &arg_types)
+}
+
+// the `udf` attribute values affecting the generated wrappers
+#[derive(Default)]
+struct UdfAttr {
+    unchecked_types: bool,
+    types: Vec<(Ident, Vec<Type>)>,
+    holder: Option<Type>,
+}
+
+// parse the `udf` attribute values affecting the generated wrappers,
+// the other values are skipped, and they are validated by the `udf` macro
+fn _parse_udf_attr(attr: &Attribute) -> UdfAttr {
+    let mut udf_attr = UdfAttr::default();
+    if let Meta::List(list) = &attr.meta {
+        let _ = list.parse_nested_meta(|meta| {
+            if meta.path.is_ident("unchecked_types") {
+                udf_attr.unchecked_types = meta.value()?.parse::<LitBool>()?.value;
+            } else if meta.path.is_ident("holder") {
+                udf_attr.holder = Some(meta.value()?.parse::<Type>()?);
+            } else if meta.path.is_ident("types") {
+                // e.g. types(T = [i32, i64], U = [f32, f64])
+                meta.parse_nested_meta(|meta| {
+                    let type_param = meta.path.require_ident()?.clone();
+                    let value = meta.value()?;
+                    let content;
+                    syn::bracketed!(content in value);
+                    let concrete_types = content.parse_terminated(Type::parse, syn::Token![,])?;
+                    udf_attr.types.push((type_param, concrete_types.into_iter().collect()));
+                    Ok(())
+                })?;
+            } else if meta.input.peek(syn::Token![=]) {
+                // the values which aren't expressions, e.g. aliases = ["old_name" => deprecated],
+                // are skipped up to the next attribute
+                let value = meta.value()?;
+                if value.fork().parse::<Expr>().is_ok() {
+                    value.parse::<Expr>()?;
+                } else {
+                    while !value.is_empty() && !value.peek(syn::Token![,]) {
+                        value.parse::<TokenTree>()?;
+                    }
+                }
+            } else {
+                meta.input.parse::<TokenTree>()?;
+            }
+            Ok(())
+        });
+    }
+    udf_attr
+}
+
+// return the type arguments of each instantiation of the function, which are all the combinations of
+// the types specified by the `types` attribute, a function with unspecified types is not instantiated
+fn _type_instantiations(sig: &Signature, types: &[(Ident, Vec<Type>)]) -> Vec<Vec<(Ident, Type)>> {
+    let mut instantiations = vec![Vec::new()];
+    for param in sig.generics.type_params() {
+        let concrete_types = types
+            .iter()
+            .find(|(type_param, _)| *type_param == param.ident)
+            .map(|(_, concrete_types)| concrete_types.as_slice())
+            .unwrap_or_default();
+        instantiations = instantiations
+            .into_iter()
+            .flat_map(|instantiation| {
+                concrete_types.iter().map(move |concrete_type| {
+                    let mut instantiation = instantiation.clone();
+                    instantiation.push((param.ident.clone(), concrete_type.clone()));
+                    instantiation
+                })
+            })
+            .collect();
+    }
+    instantiations
+}
+
+// replace the generic type parameters with their type arguments, e.g. `Option<T>` ==> `Option<i64>`
+struct TypeSubstitution<'a>(&'a [(Ident, Type)]);
+
+impl VisitMut for TypeSubstitution<'_> {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if let Type::Path(TypePath { qself: None, path }) = ty {
+            if let Some(ident) = path.get_ident() {
+                if let Some((_, concrete_type)) = self.0.iter().find(|(type_param, _)| type_param == ident) {
+                    *ty = concrete_type.clone();
+                    return;
+                }
+            }
+        }
+        syn::visit_mut::visit_type_mut(self, ty);
     }
 }
 
+fn _get_package_dir_name(path: &Path) -> Option<String> {
+    path.file_name()
+        .map(|dir_name| dir_name.to_string_lossy().into_owned())
+}
+
 // return (package_name, package_dir)
 fn _get_cargo_package_name_and_dir(path: &Path) -> Option<(String, String)> {
     let cargo_toml_path = path.join("Cargo.toml");
@@ -74,9 +224,56 @@ fn _get_cargo_package_name_and_dir(path: &Path) -> Option<(String, String)> {
     None
 }
 
+// the defaults of the UDFs in a package not specifying them, e.g.
+// [package.metadata.gandiva_udf]
+// namespace = "net"
+// category = "network"
+#[derive(Default)]
+struct PackageUdfDefaults {
+    namespace: Option<String>,
+    category: Option<String>,
+}
+
+fn _get_package_udf_defaults(path: &Path) -> PackageUdfDefaults {
+    let cargo_toml_contents = fs::read_to_string(path.join("Cargo.toml")).expect("Failed to read Cargo.toml");
+    let cargo_toml: Value = cargo_toml_contents.parse().expect("Failed to parse Cargo.toml");
+    let metadata = match cargo_toml
+        .get("package")
+        .and_then(|pkg| pkg.get("metadata"))
+        .and_then(|metadata| metadata.get("gandiva_udf"))
+    {
+        Some(metadata) => metadata,
+        None => return PackageUdfDefaults::default(),
+    };
+    let get_str = |key: &str| {
+        metadata.get(key).map(|value| {
+            value
+                .as_str()
+                .unwrap_or_else(|| panic!("`package.metadata.gandiva_udf.{}` in {} must be a string", key, path.display()))
+                .to_string()
+        })
+    };
+    let namespace = get_str("namespace");
+    if let Some(namespace) = &namespace {
+        if namespace.is_empty() || namespace.contains('.') {
+            panic!(
+                "The namespace must be a non-empty name without dots, e.g. \"net\", found \"{}\" in {}",
+                namespace,
+                path.display()
+            );
+        }
+    }
+    PackageUdfDefaults {
+        namespace,
+        category: get_str("category"),
+    }
+}
+
 // use an ordered map <package_name, Vec<String>> to store all packages with their UDFs within
 // the map is ordered so that the generated code is deterministic
-fn _extract_package_and_udfs(root_dir: &Path) -> std::collections::BTreeMap<String, (Vec<String>, String)> {
+fn _extract_package_and_udfs(
+    root_dir: &Path,
+) -> std::collections::BTreeMap<String, (Vec<String>, String, PackageUdfDefaults)> {
     let mut package_udfs = std::collections::BTreeMap::new();
     for entry in WalkDir::new(root_dir).into_iter().filter_map(|e| e.ok()) {
         let path = entry.path();
@@ -98,21 +295,22 @@ fn _extract_package_and_udfs(root_dir: &Path) -> std::collections::BTreeMap<Stri
                     syn::visit::visit_file(&mut collector, &syntax_tree);
                 }
             }
-            if collector.udf_registration_funcs.len() <= 0 {
+            if collector.udf_registration_funcs.is_empty() {
                 continue;
             }
-            package_udfs.insert(package_name, (collector.udf_registration_funcs, package_dir));
+            let defaults = _get_package_udf_defaults(path);
+            package_udfs.insert(package_name, (collector.udf_registration_funcs, package_dir, defaults));
         }
     }
     package_udfs
 }
 
 fn _collect_udf_registration_funcs(
-    package_udfs: &std::collections::BTreeMap<String, (Vec<String>, String)>,
+    package_udfs: &std::collections::BTreeMap<String, (Vec<String>, String, PackageUdfDefaults)>,
 ) -> (Vec<TokenStream>, String) {
     let mut registrations = Vec::new();
     let mut dependencies = String::new();
-    for (package_name, (udfs, package_dir)) in package_udfs.iter() {
+    for (package_name, (udfs, package_dir, defaults)) in package_udfs.iter() {
         let module_ident = format_ident!("{}", package_name);
         let registration_calls = udfs.iter().map(|fn_name| {
             let func_ident = format_ident!("register_{}", fn_name);
@@ -120,29 +318,45 @@ fn _collect_udf_registration_funcs(
                 #module_ident::#func_ident();
             }
         });
-        registrations.push(quote! {
-            #( #registration_calls )*
-        });
+        // the package defaults are only applied to the UDFs of the package
+        let registration = match defaults {
+            PackageUdfDefaults { namespace: None, category: None } => quote! {
+                #( #registration_calls )*
+            },
+            PackageUdfDefaults { namespace, category } => {
+                let namespace = _option_str_quote(namespace.as_deref());
+                let category = _option_str_quote(category.as_deref());
+                quote! {
+                    gandiva_rust_udf_shared::register_package_udfs(#namespace, #category, || {
+                        #( #registration_calls )*
+                    });
+                }
+            }
+        };
+        registrations.push(registration);
 
         let mut dep_vars: HashMap<String, String> = HashMap::new();
         dep_vars.insert("package_name".to_string(), package_name.to_string());
         dep_vars.insert("package_dir".to_string(), package_dir.to_string());
-        match strfmt(r#"{package_name} = {{ path = "../{package_dir}" }}"#, &dep_vars) {
-            Ok(dep) => {
-                let mut deps_vars: HashMap<String, String> = HashMap::new();
-                deps_vars.insert("deps".to_string(), dependencies.to_string());
-                deps_vars.insert("dep".to_string(), dep);
-                match strfmt("{deps}\n{dep}", &deps_vars) {
-                    Ok(result) => dependencies = result,
-                    _ => {}
-                }
+        if let Ok(dep) = strfmt(r#"{package_name} = {{ path = "../{package_dir}" }}"#, &dep_vars) {
+            let mut deps_vars: HashMap<String, String> = HashMap::new();
+            deps_vars.insert("deps".to_string(), dependencies.to_string());
+            deps_vars.insert("dep".to_string(), dep);
+            if let Ok(result) = strfmt("{deps}\n{dep}", &deps_vars) {
+                dependencies = result;
             }
-            _ => {}
         }
     }
     (registrations, dependencies)
 }
 
+fn _option_str_quote(value: Option<&str>) -> TokenStream {
+    match value {
+        Some(value) => quote! { Some(#value) },
+        None => quote! { None },
+    }
+}
+
 pub fn generate_udf_registry_and_dependencies(root_dir: &Path) -> (TokenStream, String) {
     let package_udfs = _extract_package_and_udfs(root_dir);
     let (registrations, dependencies) = _collect_udf_registration_funcs(&package_udfs);
@@ -0,0 +1,7 @@
+use gandiva_rust_udf_macro::udf;
+use gandiva_rust_udf_shared::GdvContext;
+
+#[udf]
+pub fn context_func(ctx: &GdvContext, x: &str) -> i64 {
+    ctx.alloc_str(x).map_or(0, |copied| copied.len() as i64)
+}
@@ -0,0 +1,6 @@
+use gandiva_rust_udf_macro::udf;
+
+#[udf(unchecked_types = true)]
+pub fn custom_func(x: UserId) -> i64 {
+    42
+}
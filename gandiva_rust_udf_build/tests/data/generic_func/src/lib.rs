@@ -0,0 +1,6 @@
+use gandiva_rust_udf_macro::udf;
+
+#[udf(aliases = ["old_generic_func" => deprecated], types(T = [i32, i64]))]
+pub fn generic_func<T>(x: T) -> T {
+    x
+}
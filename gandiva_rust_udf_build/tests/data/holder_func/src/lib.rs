@@ -0,0 +1,17 @@
+use gandiva_rust_udf_macro::udf;
+use gandiva_rust_udf_shared::UdfHolder;
+
+pub struct PrefixHolder(String);
+
+impl UdfHolder for PrefixHolder {
+    type Error = String;
+
+    fn make(literals: &[&str]) -> Result<Self, Self::Error> {
+        Ok(PrefixHolder(literals[0].to_string()))
+    }
+}
+
+#[udf(holder = PrefixHolder)]
+pub fn holder_func(holder: &PrefixHolder, x: &str, #[udf(literal)] prefix: &str) -> bool {
+    x.starts_with(&holder.0)
+}
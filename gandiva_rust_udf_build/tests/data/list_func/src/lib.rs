@@ -0,0 +1,6 @@
+use gandiva_rust_udf_macro::udf;
+
+#[udf]
+pub fn list_func(x: &[i64], y: Option<&str>) -> i64 {
+    42
+}
\ No newline at end of file
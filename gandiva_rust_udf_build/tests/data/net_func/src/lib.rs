@@ -0,0 +1,6 @@
+use gandiva_rust_udf_macro::udf;
+
+#[udf]
+pub fn net_func(x: &str) -> i64 {
+    x.len() as i64
+}
\ No newline at end of file
@@ -0,0 +1,8 @@
+use gandiva_rust_udf_macro::udf;
+use gandiva_rust_udf_shared::GdvStringWriter;
+use std::fmt::Write;
+
+#[udf]
+pub fn writer_func(x: &str, out: &mut GdvStringWriter) -> Result<(), std::fmt::Error> {
+    write!(out, "{}!", x)
+}
@@ -0,0 +1,6 @@
+use gandiva_rust_udf_macro::udf;
+
+#[udf]
+pub fn unsupported_func(x: Vec<i32>) -> usize {
+    42
+}
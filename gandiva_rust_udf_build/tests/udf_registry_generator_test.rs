@@ -4,9 +4,13 @@ mod tests {
     use std::path::PathBuf;
 
     fn scan_dir(dir: &str) -> PathBuf {
+        scan_data_dir("data", dir)
+    }
+
+    fn scan_data_dir(data_dir: &str, dir: &str) -> PathBuf {
         let mut data_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
         data_path.push("tests");
-        data_path.push("data");
+        data_path.push(data_dir);
         if !dir.is_empty() {
             data_path.push(dir);
         }
@@ -43,18 +47,150 @@ my_bar_func = { path = "../bar_func" }"#;
         assert_eq!(actual_deps, expected_deps);
     }
 
+    #[test]
+    fn test_generate_list_parameter_udf_registry() {
+        let expected_regs: proc_macro2::TokenStream = quote::quote! {
+            #[udf_registry]
+            pub fn register_all_funcs() {
+                my_list_func::register_list_func_list_int64_utf8();
+            }
+        };
+        let expected_deps = r#"
+my_list_func = { path = "../list_func" }"#;
+        let (actual_regs, actual_deps) = generate_udf_registry_and_dependencies(&scan_dir("list_func"));
+        assert_eq!(actual_regs.to_string(), expected_regs.to_string());
+        assert_eq!(actual_deps, expected_deps);
+    }
+
+    #[test]
+    fn test_generate_context_param_udf_registry() {
+        let expected_regs: proc_macro2::TokenStream = quote::quote! {
+            #[udf_registry]
+            pub fn register_all_funcs() {
+                my_context_func::register_context_func_utf8();
+            }
+        };
+        let expected_deps = r#"
+my_context_func = { path = "../context_func" }"#;
+        let (actual_regs, actual_deps) = generate_udf_registry_and_dependencies(&scan_dir("context_func"));
+        assert_eq!(actual_regs.to_string(), expected_regs.to_string());
+        assert_eq!(actual_deps, expected_deps);
+    }
+
+    #[test]
+    fn test_generate_custom_type_udf_registry() {
+        let expected_regs: proc_macro2::TokenStream = quote::quote! {
+            #[udf_registry]
+            pub fn register_all_funcs() {
+                my_custom_func::register_custom_func_userid();
+            }
+        };
+        let expected_deps = r#"
+my_custom_func = { path = "../custom_func" }"#;
+        let (actual_regs, actual_deps) = generate_udf_registry_and_dependencies(&scan_dir("custom_func"));
+        assert_eq!(actual_regs.to_string(), expected_regs.to_string());
+        assert_eq!(actual_deps, expected_deps);
+    }
+
+    #[test]
+    fn test_generate_generic_udf_registry() {
+        let expected_regs: proc_macro2::TokenStream = quote::quote! {
+            #[udf_registry]
+            pub fn register_all_funcs() {
+                my_generic_func::register_generic_func_int32();
+                my_generic_func::register_generic_func_int64();
+            }
+        };
+        let expected_deps = r#"
+my_generic_func = { path = "../generic_func" }"#;
+        let (actual_regs, actual_deps) = generate_udf_registry_and_dependencies(&scan_dir("generic_func"));
+        assert_eq!(actual_regs.to_string(), expected_regs.to_string());
+        assert_eq!(actual_deps, expected_deps);
+    }
+
+    #[test]
+    fn test_generate_holder_udf_registry() {
+        let expected_regs: proc_macro2::TokenStream = quote::quote! {
+            #[udf_registry]
+            pub fn register_all_funcs() {
+                my_holder_func::register_holder_func_utf8_utf8();
+            }
+        };
+        let expected_deps = r#"
+my_holder_func = { path = "../holder_func" }"#;
+        let (actual_regs, actual_deps) = generate_udf_registry_and_dependencies(&scan_dir("holder_func"));
+        assert_eq!(actual_regs.to_string(), expected_regs.to_string());
+        assert_eq!(actual_deps, expected_deps);
+    }
+
+    #[test]
+    fn test_generate_string_writer_udf_registry() {
+        let expected_regs: proc_macro2::TokenStream = quote::quote! {
+            #[udf_registry]
+            pub fn register_all_funcs() {
+                my_writer_func::register_writer_func_utf8();
+            }
+        };
+        let expected_deps = r#"
+my_writer_func = { path = "../writer_func" }"#;
+        let (actual_regs, actual_deps) = generate_udf_registry_and_dependencies(&scan_dir("writer_func"));
+        assert_eq!(actual_regs.to_string(), expected_regs.to_string());
+        assert_eq!(actual_deps, expected_deps);
+    }
+
+    #[test]
+    fn test_generate_package_defaults_udf_registry() {
+        // the defaults in `[package.metadata.gandiva_udf]` are applied to the UDFs of the package
+        let expected_regs: proc_macro2::TokenStream = quote::quote! {
+            #[udf_registry]
+            pub fn register_all_funcs() {
+                gandiva_rust_udf_shared::register_package_udfs(Some("net"), Some("network"), || {
+                    my_net_func::register_net_func_utf8();
+                });
+            }
+        };
+        let expected_deps = r#"
+my_net_func = { path = "../net_func" }"#;
+        let (actual_regs, actual_deps) = generate_udf_registry_and_dependencies(&scan_dir("net_func"));
+        assert_eq!(actual_regs.to_string(), expected_regs.to_string());
+        assert_eq!(actual_deps, expected_deps);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unsupported parameter type `Vec < i32 >` of UDF `unsupported_func`")]
+    fn test_generate_unsupported_type_udf_registry() {
+        generate_udf_registry_and_dependencies(&scan_data_dir("invalid_data", "unsupported_func"));
+    }
+
     #[test]
     fn test_generate_multi_udfs_registry() {
         let expected_regs: proc_macro2::TokenStream = quote::quote! {
             #[udf_registry]
             pub fn register_all_funcs() {
                 my_bar_func::register_bar_func_int32();
+                my_context_func::register_context_func_utf8();
+                my_custom_func::register_custom_func_userid();
                 my_foo_func::register_foo_func_();
+                my_generic_func::register_generic_func_int32();
+                my_generic_func::register_generic_func_int64();
+                my_holder_func::register_holder_func_utf8_utf8();
+                my_list_func::register_list_func_list_int64_utf8();
+                gandiva_rust_udf_shared::register_package_udfs(Some("net"), Some("network"), || {
+                    my_net_func::register_net_func_utf8();
+                });
+                my_writer_func::register_writer_func_utf8();
             }
         };
         let expected_deps = r#"
 my_bar_func = { path = "../bar_func" }
-my_foo_func = { path = "../foo_func" }"#;
+my_context_func = { path = "../context_func" }
+my_custom_func = { path = "../custom_func" }
+my_foo_func = { path = "../foo_func" }
+my_generic_func = { path = "../generic_func" }
+my_holder_func = { path = "../holder_func" }
+my_list_func = { path = "../list_func" }
+my_net_func = { path = "../net_func" }
+my_writer_func = { path = "../writer_func" }"#;
         // use empty string to scan the whole directory
         let (actual_regs, actual_deps) = generate_udf_registry_and_dependencies(&scan_dir(""));
         assert_eq!(actual_regs.to_string(), expected_regs.to_string());
@@ -1,7 +1,252 @@
-// map type from Rust name into Arrow type name
+// strip the `Option` wrapper of a nullable type, e.g. "Option < i64 >" ==> "i64"
+// return None if the type is not an `Option`
+pub fn strip_option_type(arg_type: &str) -> Option<&str> {
+    arg_type
+        .trim()
+        .strip_prefix("Option")
+        .and_then(|inner| inner.trim_start().strip_prefix('<'))
+        .and_then(|inner| inner.strip_suffix('>'))
+        .map(|inner| inner.trim())
+}
+
+// remove the lifetimes of a borrowed type, e.g. "& 'a str" ==> "& str"
+pub fn strip_lifetimes(arg_type: &str) -> String {
+    arg_type
+        .split_whitespace()
+        .filter(|token| !token.starts_with('\''))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// whether the type is a borrowed-or-owned string, e.g. "Cow < 'a , str >", which is used for UTF-8 lossy parameters
+pub fn is_cow_str(arg_type: &str) -> bool {
+    let arg_type: String = strip_lifetimes(arg_type).split_whitespace().collect();
+    // the type may be referred with its path, e.g. "std::borrow::Cow<str>"
+    let arg_type = arg_type.rsplit("::").next().unwrap_or(&arg_type);
+    matches!(arg_type, "Cow<str>" | "Cow<,str>")
+}
+
+// whether the type is the gandiva function context, e.g. "& GdvContext", which is passed to the UDF
+// as its first parameter instead of a Gandiva parameter
+pub fn is_context_type(arg_type: &str) -> bool {
+    let arg_type: String = strip_lifetimes(arg_type).split_whitespace().collect();
+    // the context is borrowed from the wrapper function, so it cannot outlive the call
+    arg_type.strip_prefix('&').is_some_and(is_context_value_type)
+}
+
+// whether the type is the gandiva function context taken by value, e.g. "GdvContext",
+// which is rejected because the context must be borrowed
+pub fn is_context_value_type(arg_type: &str) -> bool {
+    let arg_type: String = strip_lifetimes(arg_type).split_whitespace().collect();
+    // the type may be referred with its path, e.g. "gandiva_rust_udf_shared::GdvContext"
+    let arg_type = arg_type.split('<').next().unwrap_or(&arg_type);
+    arg_type.rsplit("::").next() == Some("GdvContext")
+}
+
+// whether the type is the writer of a string result, e.g. "& mut GdvStringWriter < '_ >", which is passed to the UDF
+// instead of a Gandiva parameter, and the UDF writes its result into it
+pub fn is_string_writer_type(arg_type: &str) -> bool {
+    let arg_type: String = strip_lifetimes(arg_type).split_whitespace().collect();
+    let arg_type = match arg_type.strip_prefix("&mut") {
+        Some(arg_type) => arg_type,
+        None => return false,
+    };
+    let arg_type = arg_type.split('<').next().unwrap_or(arg_type);
+    arg_type.rsplit("::").next() == Some("GdvStringWriter")
+}
+
+// return the byte width of a fixed size byte array type, e.g. "[u8 ; 16]" ==> 16
+// return None if the type is not a fixed size byte array
+pub fn fixed_size_binary_width(arg_type: &str) -> Option<i32> {
+    // the spacing of the stringified tokens may vary, e.g. "[u8; 16]" from the compiler
+    let arg_type: String = arg_type.split_whitespace().collect();
+    arg_type
+        .strip_prefix("[u8;")
+        .and_then(|width| width.strip_suffix(']'))
+        .and_then(|width| width.parse().ok())
+}
+
+// return the precision and scale of a decimal type, e.g. "Decimal128 < 10 , 2 >" ==> (10, 2)
+// return None if the type is not a decimal
+pub fn decimal_precision_scale(arg_type: &str) -> Option<(i32, i32)> {
+    let arg_type: String = arg_type.split_whitespace().collect();
+    // the type may be referred with its path, e.g. "gandiva_rust_udf_shared::Decimal128<10,2>"
+    let arg_type = arg_type.rsplit("::").next().unwrap_or(&arg_type);
+    let (precision, scale) = arg_type
+        .strip_prefix("Decimal128<")
+        .and_then(|params| params.strip_suffix('>'))
+        .and_then(|params| params.split_once(','))?;
+    Some((precision.parse().ok()?, scale.parse().ok()?))
+}
+
+// map a date/time type into its Arrow type name and time unit,
+// e.g. "Timestamp < Millisecond >" ==> ("timestamp", Some("MILLI")), "Date32" ==> ("date32", None)
+// return None if the type is not a date/time type
+pub fn map_temporal_type(arg_type: &str) -> Option<(String, Option<String>)> {
+    let arg_type: String = arg_type.split_whitespace().collect();
+    let (base_type, unit_type) = match arg_type.split_once('<') {
+        Some((base_type, unit_type)) => (base_type, unit_type.strip_suffix('>')?),
+        None => (arg_type.as_str(), ""),
+    };
+    // the types may be referred with their paths, e.g. "gandiva_rust_udf_shared::Timestamp<Second>"
+    let base_type = base_type.rsplit("::").next()?;
+    let unit_type = unit_type.rsplit("::").next()?;
+    let unit = match unit_type {
+        "Second" => Some("SECOND"),
+        "Millisecond" => Some("MILLI"),
+        "Microsecond" => Some("MICRO"),
+        "Nanosecond" => Some("NANO"),
+        _ => None,
+    };
+    let type_name = match (base_type, unit_type) {
+        ("Date32", "") => "date32",
+        ("Date64", "") => "date64",
+        ("Time32", "Second" | "Millisecond") => "time32",
+        ("Time64", "Microsecond" | "Nanosecond") => "time64",
+        ("Timestamp", _) if unit.is_some() => "timestamp",
+        _ => return None,
+    };
+    Some((type_name.to_string(), unit.map(|unit| unit.to_string())))
+}
+
+// return the Arrow type name of the list value type, e.g. "& [i64]" ==> "int64", "StrList < 'a >" ==> "utf8"
+// return None if the type is not a list, note that "& [u8]" is `binary` instead of a list
+pub fn list_value_type(arg_type: &str) -> Option<String> {
+    let arg_type: String = strip_lifetimes(arg_type).split_whitespace().collect();
+    if arg_type.rsplit("::").next()?.starts_with("StrList") {
+        return Some("utf8".to_string());
+    }
+    let value_type = arg_type.strip_prefix("&[")?.strip_suffix(']')?;
+    match value_type {
+        "i8" | "i16" | "i32" | "i64" | "u16" | "u32" | "u64" | "f32" | "f64" => {
+            Some(map_type(value_type))
+        }
+        _ => None,
+    }
+}
+
+// the type name used in the wrapper function name, which is the Arrow type name,
+// and list types also include their value type, e.g. "list_int64",
+// custom types are named after their Rust type, e.g. "my_mod :: MyId" ==> "my_mod_myid"
+pub fn signature_type_name(arg_type: &str) -> String {
+    let arg_type = strip_option_type(arg_type).unwrap_or(arg_type);
+    if let Some(value_type) = list_value_type(arg_type) {
+        return format!("list_{}", value_type);
+    }
+    match try_map_type(arg_type) {
+        Some(type_name) => type_name,
+        None => arg_type
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("_")
+            .to_lowercase(),
+    }
+}
+
+// whether the Arrow type is passed as a data pointer along with its length
+pub fn is_var_len_type(gdv_type: &str) -> bool {
+    matches!(gdv_type, "utf8" | "binary" | "fixed_size_binary")
+}
+
+// the supported UDF parameter types, which are listed in the compile error of an unsupported parameter type
+pub const SUPPORTED_PARAM_TYPES: &str = "bool, i8, i16, i32, i64, u8, u16, u32, u64, f32, f64, &str, Cow<str>, &[u8], \
+    Decimal128<P, S>, Date32, Date64, Time32<U>, Time64<U>, Timestamp<U>, &[T] of numeric types, StrList, \
+    and `Option` of them";
+
+// the supported UDF return types, which are listed in the compile error of an unsupported return type
+pub const SUPPORTED_RETURN_TYPES: &str = "bool, i8, i16, i32, i64, u8, u16, u32, u64, f32, f64, String, &str, \
+    Vec<u8>, Box<[u8]>, [u8; N], Decimal128<P, S>, Date32, Date64, Time32<U>, Time64<U>, Timestamp<U>, \
+    and `Option`/`Result` of them";
+
+// whether the Rust type can be used as a UDF parameter, `Option<T>` is supported if `T` is supported
+pub fn is_supported_param_type(arg_type: &str) -> bool {
+    let arg_type = strip_lifetimes(strip_option_type(arg_type).unwrap_or(arg_type));
+    if strip_option_type(&arg_type).is_some() {
+        return false;
+    }
+    match try_map_type(&arg_type).as_deref() {
+        // owned strings/bytes and fixed size byte arrays are only supported as return types
+        Some("utf8") => arg_type == "& str" || is_cow_str(&arg_type),
+        Some("binary") => arg_type == "& [u8]",
+        Some("fixed_size_binary") => false,
+        Some(_) => true,
+        None => false,
+    }
+}
+
+// whether the Rust type can be returned from a UDF, `Option<T>` is supported if `T` is supported,
+// the `Result` wrapper should be stripped before checking, see `strip_result_type`
+pub fn is_supported_return_type(return_type: &str) -> bool {
+    let return_type = strip_lifetimes(strip_option_type(return_type).unwrap_or(return_type));
+    if strip_option_type(&return_type).is_some() {
+        return false;
+    }
+    match try_map_type(&return_type).as_deref() {
+        // borrowed bytes and lists are only supported as parameter types,
+        // a borrowed string result is returned without copying if it points into a parameter
+        Some("utf8") => return_type == "String" || return_type == "& str",
+        Some("binary") => return_type != "& [u8]",
+        Some("list") => false,
+        Some(_) => true,
+        None => false,
+    }
+}
+
+// return the success type of a `Result` type, e.g. "Result < Option < i64 > , String >" ==> "Option < i64 >"
+// return None if the type is not a `Result`
+pub fn strip_result_type(return_type: &str) -> Option<&str> {
+    let inner = return_type
+        .trim()
+        .strip_prefix("Result")?
+        .trim_start()
+        .strip_prefix('<')?
+        .strip_suffix('>')?;
+    // split at the first top level comma, the success type may have generic parameters
+    let mut depth = 0;
+    for (i, c) in inner.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => return Some(inner[..i].trim()),
+            _ => {}
+        }
+    }
+    None
+}
+
+// map type from Rust name into Arrow type name, `Option<T>` is mapped as `T`,
+// unknown types are mapped as is, use `try_map_type` to detect them
 pub fn map_type(arg_type: &str) -> String {
-    match arg_type {
+    let arg_type = strip_option_type(arg_type).unwrap_or(arg_type);
+    try_map_type(arg_type).unwrap_or_else(|| arg_type.to_string())
+}
+
+// map type from Rust name into Arrow type name, `Option<T>` is mapped as `T`
+// return None if the type cannot be mapped into an Arrow type
+pub fn try_map_type(arg_type: &str) -> Option<String> {
+    let arg_type = strip_option_type(arg_type).unwrap_or(arg_type);
+    if fixed_size_binary_width(arg_type).is_some() {
+        return Some("fixed_size_binary".to_string());
+    }
+    if decimal_precision_scale(arg_type).is_some() {
+        return Some("decimal".to_string());
+    }
+    if let Some((type_name, _)) = map_temporal_type(arg_type) {
+        return Some(type_name);
+    }
+    if list_value_type(arg_type).is_some() {
+        return Some("list".to_string());
+    }
+    if is_cow_str(arg_type) {
+        return Some("utf8".to_string());
+    }
+    let type_name = match strip_lifetimes(arg_type).as_str() {
         "& str" => "utf8",
+        "String" => "utf8",
+        "& [u8]" => "binary",
+        "Vec < u8 >" => "binary",
+        "Box < [u8] >" => "binary",
         "bool" => "boolean",
         "i8" => "int8",
         "i16" => "int16",
@@ -13,7 +258,88 @@ pub fn map_type(arg_type: &str) -> String {
         "u64" => "uint64",
         "f32" => "float32",
         "f64" => "float64",
-        _ => arg_type,
+        _ => return None,
+    };
+    Some(type_name.to_string())
+}
+
+// whether the function name or alias can be registered, which must be an identifier, e.g. "my_func",
+// since it is called by its name in the expressions
+pub fn is_valid_function_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() || first == '_' => {
+            chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        _ => false,
+    }
+}
+
+// the functions built into the Gandiva function registry, which are shadowed by the UDFs registered with the same names,
+// keywords and constants of the expression language such as `and` and `e` are not registry functions
+pub const GANDIVA_BUILTIN_FUNCTIONS: &[&str] = &[
+    // arithmetic
+    "add", "subtract", "multiply", "divide", "div", "mod", "modulo", "pmod", "negative", "positive", "abs",
+    "ceil", "ceiling", "floor", "round", "truncate", "trunc", "sqrt", "cbrt", "exp", "log", "log10", "power", "pow",
+    "sign", "signum", "bitwise_and", "bitwise_or", "bitwise_xor", "bitwise_not", "shift_left", "shift_right",
+    "greatest", "least", "rand", "random", "degrees", "radians", "pi",
+    "sin", "cos", "tan", "asin", "acos", "atan", "atan2", "sinh", "cosh", "tanh", "cot",
+    // comparison and logic
+    "equal", "not_equal", "eq_ignore_case", "less_than", "less_than_or_equal_to", "greater_than",
+    "greater_than_or_equal_to", "isnull", "isnotnull", "isnumeric", "is_distinct_from", "is_not_distinct_from",
+    "istrue", "isfalse", "isnottrue", "isnotfalse", "nvl",
+    // string
+    "upper", "lower", "initcap", "length", "char_length", "octet_length", "bit_length", "concat", "concatOperator",
+    "substr", "substring", "left", "right", "lpad", "rpad", "ltrim", "rtrim", "btrim", "trim", "reverse", "repeat",
+    "replace", "translate", "like", "ilike", "regexp_like", "regexp_replace", "regexp_extract", "starts_with",
+    "ends_with", "locate", "position", "strpos", "split_part", "space", "ascii", "chr", "soundex", "levenshtein",
+    "quote", "elt", "find_in_set", "instr", "to_hex", "from_hex", "unhex", "base64", "unbase64", "binary_string",
+    "byte_substr", "convert_fromUTF8", "convert_replaceUTF8", "convert_toDOUBLE", "convert_toFLOAT",
+    "convert_toINT", "convert_toBIGINT", "convert_toBOOLEAN_BYTE", "convert_toTIME_EPOCH",
+    "convert_toTIMESTAMP_EPOCH", "convert_toDATE_EPOCH", "convert_toUTF8", "mask", "mask_first_n", "mask_last_n",
+    "mask_show_first_n", "mask_show_last_n", "parse_url", "aes_encrypt", "aes_decrypt",
+    // cast
+    "castBIT", "castBOOLEAN", "castINT", "castBIGINT", "castFLOAT4", "castFLOAT8", "castDECIMAL",
+    "castDECIMALNullOnOverflow", "castVARCHAR", "castVARBINARY", "castBINARY", "castCHAR", "castDATE",
+    "castTIME", "castTIMESTAMP", "castINTERVALDAY", "castINTERVALYEAR", "castNULLABLEINT", "castNULLABLEBIGINT",
+    "castNULLABLEFLOAT4", "castNULLABLEFLOAT8", "castNULLABLEINTERVALDAY", "castNULLABLEINTERVALYEAR",
+    "to_date", "to_time", "to_timestamp", "to_utc_timestamp", "from_utc_timestamp", "unix_timestamp",
+    "from_unixtime", "format_number",
+    // date and time
+    "extractMillennium", "extractCentury", "extractDecade", "extractYear", "extractDoy", "extractQuarter",
+    "extractMonth", "extractWeek", "extractDow", "extractDay", "extractHour", "extractMinute", "extractSecond",
+    "extractEpoch", "date_trunc_Millennium", "date_trunc_Century", "date_trunc_Decade", "date_trunc_Year",
+    "date_trunc_Quarter", "date_trunc_Month", "date_trunc_Week", "date_trunc_Day", "date_trunc_Hour",
+    "date_trunc_Minute", "date_trunc_Second", "date_add", "date_sub", "date_diff", "add_months", "months_between",
+    "last_day", "next_day", "datediff", "timestampaddSecond", "timestampaddMinute", "timestampaddHour",
+    "timestampaddDay", "timestampaddWeek", "timestampaddMonth", "timestampaddQuarter", "timestampaddYear",
+    "timestampdiffSecond", "timestampdiffMinute", "timestampdiffHour", "timestampdiffDay", "timestampdiffWeek",
+    "timestampdiffMonth", "timestampdiffQuarter", "timestampdiffYear", "year", "month", "day", "hour", "minute",
+    "second", "quarter", "weekofyear", "dayofweek", "dayofmonth", "dayofyear", "current_date", "current_time",
+    "current_timestamp", "now", "localtime", "localtimestamp",
+    // hash
+    "hash", "hash32", "hash64", "hash32AsDouble", "hash64AsDouble", "hashSHA1", "hashSHA256", "hashSHA512",
+    "hashMD5", "sha", "sha1", "sha256", "sha512", "md5", "crc32",
+];
+
+// whether the function name shadows a function built into Gandiva, the names are compared case-insensitively
+// since hosts may resolve function names case-insensitively
+pub fn is_gandiva_builtin(name: &str) -> bool {
+    GANDIVA_BUILTIN_FUNCTIONS.iter().any(|builtin| builtin.eq_ignore_ascii_case(name))
+}
+
+// the name of the wrapper function generated by the `udf` macro, which is also used to name its registration
+// function `register_{wrapper}`, e.g. ("my_func", ["int64", "utf8"]) ==> "my_func_int64_utf8"
+pub fn wrapper_function_name(function_name: &str, arg_types: &[String]) -> String {
+    format!("{}_{}", function_name, arg_types.join("_"))
+}
+
+// the symbol exported for the wrapper function, which is prefixed with the name of the crate defining the UDF
+// so that the UDFs with the same signature in different crates of the same library don't collide,
+// e.g. (Some("my_crate"), "my_func_int64") ==> "my_crate__my_func_int64"
+pub fn wrapper_symbol_name(crate_name: Option<&str>, wrapper_name: &str) -> String {
+    match crate_name {
+        Some(crate_name) => format!("{}__{}", crate_name, wrapper_name),
+        None => wrapper_name.to_string(),
     }
-    .to_string()
 }
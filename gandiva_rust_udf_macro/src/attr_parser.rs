@@ -1,96 +1,576 @@
-use syn::parse::{Parser};
-use syn::{Attribute, GenericArgument, ItemFn, LitBool, LitStr, ReturnType};
+use syn::parse::Parser;
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::parse::Parse;
+use gandiva_rust_udf_common::is_valid_function_name;
+use syn::{Attribute, Expr, GenericArgument, ItemFn, LitBool, LitStr, ReturnType, Type};
+
+// the UDF meta specified in the #[udf(...)] macro attributes
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct UdfMeta {
+    pub(crate) name: Option<String>,
+    pub(crate) aliases: Vec<String>,
+    pub(crate) needs_context: bool,
+    pub(crate) can_return_errors: bool,
+    pub(crate) result_nullable: Option<String>,
+    // allow custom types implementing `gandiva_rust_udf_shared::GdvType` besides the supported types
+    pub(crate) unchecked_types: bool,
+    // how a panic in the UDF is handled, "error" (by default) or "abort"
+    pub(crate) panic: Option<String>,
+    // how invalid UTF-8 in `&str` parameters is handled, "error" (by default), "lossy" or "unchecked"
+    pub(crate) utf8: Option<String>,
+    // the types to instantiate each generic type parameter with, e.g. [("T", ["i32", "i64"])]
+    pub(crate) types: Vec<(String, Vec<String>)>,
+    // the prefix added to the messages of the errors returned from the UDF
+    pub(crate) error_prefix: Option<String>,
+    // how an error returned from the UDF is handled, "fail" (by default), "null" or "default"
+    pub(crate) on_error: Option<String>,
+    // the expression returned for an error with `on_error = "default"`, e.g. "- 1"
+    pub(crate) default: Option<String>,
+    // the type of the holder made from the literal parameters, e.g. "RegexHolder", see `UdfHolder`
+    pub(crate) holder: Option<String>,
+    // the names of the parameters which must be literals, besides the ones marked by parameter attributes
+    pub(crate) literal: Vec<String>,
+    // the description of the function, which is taken from the doc comments if not specified
+    pub(crate) description: Option<String>,
+    // the usage examples of the function, e.g. ["my_udf(1) => 2"]
+    pub(crate) examples: Vec<String>,
+    // whether the result may change for the same arguments, "immutable" (by default), "stable" or "volatile",
+    // `deterministic = false` is a shorthand of "volatile" and `deterministic = true` of "immutable"
+    pub(crate) volatility: Option<String>,
+    // the deprecation message of the function and the version since which it is deprecated
+    pub(crate) deprecated: Option<String>,
+    pub(crate) since: Option<String>,
+    // the aliases which are deprecated, e.g. aliases = ["old_name" => deprecated]
+    pub(crate) deprecated_aliases: Vec<String>,
+    // the namespace of the function, e.g. "net" for `net.parse`, and its category for documentation
+    pub(crate) namespace: Option<String>,
+    pub(crate) category: Option<String>,
+    // how a name or an alias shadowing a Gandiva built-in function is handled, "warn" (by default), "error" or "allow"
+    pub(crate) shadow_builtin: Option<String>,
+    // the symbol exported for the wrapper function, which is registered as its `pc_name`
+    pub(crate) pc_name: Option<String>,
+    // the name of the crate defining the function, which is taken from the build environment instead of
+    // the attributes, and prefixed to the exported symbol by default
+    pub(crate) crate_name: Option<String>,
+}
+
+// the registry meta specified in the #[udf_registry(...)] macro attributes, which are the defaults
+// for the UDFs not specifying them
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct RegistryMeta {
+    pub(crate) namespace: Option<String>,
+    pub(crate) category: Option<String>,
+}
+
+// the parameter meta specified in the #[udf(...)] parameter attributes
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct ParamMeta {
+    // how invalid UTF-8 in the parameter is handled, which overrides the function one
+    pub(crate) utf8: Option<String>,
+    // whether the parameter must be a literal
+    pub(crate) literal: bool,
+}
 
 // Extract UDF meta from the #[udf(name="my_func", aliases = ["my_func1", "my_func2"])] macro attributes, including:
 // 1) name
 // 2) aliases
 // 3) needs_context, needs_context can now be automatically determined by return_type
-// return a tuple of (name, aliases, needs_context)
+// 4) can_return_errors
+// 5) result_nullable
+// 6) unchecked_types
+// 7) panic
+// 8) utf8
+// 9) types
+// 10) error_prefix
+// 11) on_error
+// 12) default
+// 13) holder
+// 14) literal
+// 15) description
+// 16) examples
+// 17) deterministic
+// 18) volatility
+// 19) deprecated
+// 20) since
+// 21) namespace
+// 22) category
+// 23) shadow_builtin
+// 24) pc_name
 pub(crate) fn extract_udf_meta(
     input: proc_macro2::TokenStream,
-) -> Result<(Option<String>, Vec<String>, bool, bool, Option<String>), syn::Error> {
+) -> Result<UdfMeta, syn::Error> {
     let mut name = None;
     let mut aliases = Vec::new();
     let mut needs_context = false;
     let mut can_return_errors = false;
     let mut result_nullable = None;
+    let mut unchecked_types = false;
+    let mut panic = None;
+    let mut utf8 = None;
+    let mut types = Vec::new();
+    let mut error_prefix = None;
+    let mut on_error = None;
+    let mut default = None;
+    let mut holder = None;
+    let mut literal = Vec::new();
+    let mut description = None;
+    let mut examples = Vec::new();
+    let mut volatility = None;
+    let mut deprecated = None;
+    let mut since = None;
+    let mut deprecated_aliases = Vec::new();
+    let mut namespace = None;
+    let mut category = None;
+    let mut shadow_builtin = None;
+    let mut pc_name = None;
+    // the name and aliases are validated with their spans after all attributes are parsed
+    let mut name_lit = None;
+    let mut alias_lits = Vec::new();
 
-    // this is a workaround to parse the attributes
-    // https://github.com/dtolnay/syn/issues/359
-    let attr_text = format!("#[udf({})]", input.to_string());
-    let attrs = Attribute::parse_outer.parse2(attr_text.parse()?)?;
-    for attr in attrs {
-        if attr.path().is_ident("udf") {
-            attr.parse_nested_meta(|meta| {
-                if meta.path.is_ident("name") {
-                    let value = meta.value()?;
-                    let s: LitStr = value.parse()?;
-                    name = Some(s.value());
-                    Ok(())
-                } else if meta.path.is_ident("aliases") {
-                    let value = meta.value()?;
-                    let list: syn::ExprArray = value.parse()?;
-                    for expr in list.elems {
-                        if let syn::Expr::Lit(expr_lit) = expr {
-                            if let syn::Lit::Str(lit_str) = &expr_lit.lit {
-                                aliases.push(lit_str.value());
-                            } else {
+    // parse the attribute tokens directly so that errors point at the offending tokens
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("name") {
+            let value = meta.value()?;
+            let s: LitStr = value.parse()?;
+            name = Some(s.value());
+            name_lit = Some(s);
+            Ok(())
+        } else if meta.path.is_ident("aliases") {
+            // e.g. aliases = ["my_func1", "old_name" => deprecated]
+            let value = meta.value()?;
+            let content;
+            syn::bracketed!(content in value);
+            while !content.is_empty() {
+                let expr: Expr = content.parse()?;
+                if let syn::Expr::Lit(expr_lit) = expr {
+                    if let syn::Lit::Str(lit_str) = &expr_lit.lit {
+                        aliases.push(lit_str.value());
+                        alias_lits.push(lit_str.clone());
+                        if content.peek(syn::Token![=>]) {
+                            content.parse::<syn::Token![=>]>()?;
+                            let flag: syn::Ident = content.parse()?;
+                            if flag != "deprecated" {
                                 return Err(syn::Error::new_spanned(
-                                    expr_lit,
-                                    "Expected string literal for function alias",
+                                    flag,
+                                    "Unsupported alias flag. Only deprecated is allowed.",
                                 ));
                             }
-                        } else {
-                            return Err(syn::Error::new_spanned(
-                                expr,
-                                "Expected string literal for function alias",
-                            ));
+                            deprecated_aliases.push(lit_str.value());
                         }
-                    }
-                    Ok(())
-                } else if meta.path.is_ident("needs_context") {
-                    let value = meta.value()?;
-                    let b: LitBool = value.parse()?;
-                    needs_context = b.value;
-                    Ok(())
-                } else if meta.path.is_ident("can_return_errors") {
-                    let value = meta.value()?;
-                    let b: LitBool = value.parse()?;
-                    can_return_errors = b.value;
-                    Ok(())
-                } else if meta.path.is_ident("result_nullable") {
-                    let value = meta.value()?;
-                    let s: LitStr = value.parse()?;
-                    result_nullable = Some(s.value());
-                    // only if_null/never/internal are allowed
-                    if result_nullable.as_ref().unwrap() != "if_null"
-                        && result_nullable.as_ref().unwrap() != "never"
-                        && result_nullable.as_ref().unwrap() != "internal"
-                    {
+                    } else {
                         return Err(syn::Error::new_spanned(
-                            meta.path,
-                            "Unsupported value for result_nullable attribute. \
-                            Only if_null, never, internal are allowed.",
+                            expr_lit,
+                            "Expected string literal for function alias",
                         ));
                     }
-                    Ok(())
                 } else {
-                    Err(syn::Error::new_spanned(
-                        meta.path,
-                        "Unknown attribute for UDF function",
-                    ))
+                    return Err(syn::Error::new_spanned(
+                        expr,
+                        "Expected string literal for function alias",
+                    ));
+                }
+                if !content.is_empty() {
+                    content.parse::<syn::Token![,]>()?;
                 }
-            })?;
+            }
+            Ok(())
+        } else if meta.path.is_ident("needs_context") {
+            let value = meta.value()?;
+            let b: LitBool = value.parse()?;
+            needs_context = b.value;
+            Ok(())
+        } else if meta.path.is_ident("can_return_errors") {
+            let value = meta.value()?;
+            let b: LitBool = value.parse()?;
+            can_return_errors = b.value;
+            Ok(())
+        } else if meta.path.is_ident("result_nullable") {
+            let value = meta.value()?;
+            let s: LitStr = value.parse()?;
+            result_nullable = Some(s.value());
+            // only if_null/never/internal are allowed
+            if result_nullable.as_ref().unwrap() != "if_null"
+                && result_nullable.as_ref().unwrap() != "never"
+                && result_nullable.as_ref().unwrap() != "internal"
+            {
+                return Err(syn::Error::new_spanned(
+                    s,
+                    "Unsupported value for result_nullable attribute. \
+                    Only if_null, never, internal are allowed.",
+                ));
+            }
+            Ok(())
+        } else if meta.path.is_ident("unchecked_types") {
+            let value = meta.value()?;
+            let b: LitBool = value.parse()?;
+            unchecked_types = b.value;
+            Ok(())
+        } else if meta.path.is_ident("panic") {
+            let value = meta.value()?;
+            let s: LitStr = value.parse()?;
+            // only error/abort are allowed
+            if s.value() != "error" && s.value() != "abort" {
+                return Err(syn::Error::new_spanned(
+                    s,
+                    "Unsupported value for panic attribute. Only error, abort are allowed.",
+                ));
+            }
+            panic = Some(s.value());
+            Ok(())
+        } else if meta.path.is_ident("utf8") {
+            utf8 = Some(_parse_utf8_policy(&meta)?);
+            Ok(())
+        } else if meta.path.is_ident("types") {
+            // e.g. types(T = [i32, i64], U = [f32, f64])
+            meta.parse_nested_meta(|meta| {
+                let type_param = meta
+                    .path
+                    .get_ident()
+                    .ok_or_else(|| meta.error("Expected a generic type parameter name"))?
+                    .to_string();
+                let value = meta.value()?;
+                let content;
+                syn::bracketed!(content in value);
+                let concrete_types = content.parse_terminated(Type::parse, syn::Token![,])?;
+                if concrete_types.is_empty() {
+                    return Err(meta.error("Expected at least one type to instantiate with"));
+                }
+                let concrete_types = concrete_types.iter().map(|ty| quote!(#ty).to_string()).collect();
+                types.push((type_param, concrete_types));
+                Ok(())
+            })
+        } else if meta.path.is_ident("error_prefix") {
+            let value = meta.value()?;
+            let s: LitStr = value.parse()?;
+            error_prefix = Some(s.value());
+            Ok(())
+        } else if meta.path.is_ident("on_error") {
+            let value = meta.value()?;
+            let s: LitStr = value.parse()?;
+            // only fail/null/default are allowed
+            if s.value() != "fail" && s.value() != "null" && s.value() != "default" {
+                return Err(syn::Error::new_spanned(
+                    s,
+                    "Unsupported value for on_error attribute. Only fail, null, default are allowed.",
+                ));
+            }
+            on_error = Some(s.value());
+            Ok(())
+        } else if meta.path.is_ident("default") {
+            let value = meta.value()?;
+            let expr: Expr = value.parse()?;
+            default = Some(expr);
+            Ok(())
+        } else if meta.path.is_ident("holder") {
+            let value = meta.value()?;
+            let ty: Type = value.parse()?;
+            holder = Some(quote!(#ty).to_string());
+            Ok(())
+        } else if meta.path.is_ident("literal") {
+            let value = meta.value()?;
+            let list: syn::ExprArray = value.parse()?;
+            for expr in list.elems {
+                let param_name = match &expr {
+                    syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit_str), .. }) => lit_str.value(),
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            expr,
+                            "Expected string literal for literal parameter name",
+                        ))
+                    }
+                };
+                if literal.contains(&param_name) {
+                    return Err(syn::Error::new_spanned(
+                        expr,
+                        format!("Literal parameter `{}` is specified more than once.", param_name),
+                    ));
+                }
+                literal.push(param_name);
+            }
+            Ok(())
+        } else if meta.path.is_ident("description") {
+            let value = meta.value()?;
+            let s: LitStr = value.parse()?;
+            description = Some(s.value());
+            Ok(())
+        } else if meta.path.is_ident("examples") {
+            let value = meta.value()?;
+            let list: syn::ExprArray = value.parse()?;
+            for expr in list.elems {
+                match &expr {
+                    syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit_str), .. }) => examples.push(lit_str.value()),
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            expr,
+                            "Expected string literal for function example",
+                        ))
+                    }
+                }
+            }
+            Ok(())
+        } else if meta.path.is_ident("deprecated") {
+            let value = meta.value()?;
+            let s: LitStr = value.parse()?;
+            deprecated = Some(s.value());
+            Ok(())
+        } else if meta.path.is_ident("since") {
+            let value = meta.value()?;
+            let s: LitStr = value.parse()?;
+            since = Some(s);
+            Ok(())
+        } else if meta.path.is_ident("namespace") {
+            namespace = Some(_parse_namespace(&meta)?);
+            Ok(())
+        } else if meta.path.is_ident("category") {
+            let value = meta.value()?;
+            let s: LitStr = value.parse()?;
+            category = Some(s.value());
+            Ok(())
+        } else if meta.path.is_ident("pc_name") {
+            let value = meta.value()?;
+            let s: LitStr = value.parse()?;
+            if !is_valid_function_name(&s.value()) {
+                return Err(syn::Error::new_spanned(
+                    &s,
+                    format!("Invalid pc_name `{}`, the exported symbol must be an identifier.", s.value()),
+                ));
+            }
+            pc_name = Some(s.value());
+            Ok(())
+        } else if meta.path.is_ident("shadow_builtin") {
+            let value = meta.value()?;
+            let s: LitStr = value.parse()?;
+            // only warn/error/allow are allowed
+            if s.value() != "warn" && s.value() != "error" && s.value() != "allow" {
+                return Err(syn::Error::new_spanned(
+                    s,
+                    "Unsupported value for shadow_builtin attribute. Only warn, error, allow are allowed.",
+                ));
+            }
+            shadow_builtin = Some(s.value());
+            Ok(())
+        } else if meta.path.is_ident("deterministic") || meta.path.is_ident("volatility") {
+            if volatility.is_some() {
+                return Err(meta.error("Only one of deterministic and volatility can be specified."));
+            }
+            if meta.path.is_ident("deterministic") {
+                let value = meta.value()?;
+                let b: LitBool = value.parse()?;
+                volatility = Some(if b.value { "immutable" } else { "volatile" }.to_string());
+            } else {
+                let value = meta.value()?;
+                let s: LitStr = value.parse()?;
+                // only immutable/stable/volatile are allowed
+                if s.value() != "immutable" && s.value() != "stable" && s.value() != "volatile" {
+                    return Err(syn::Error::new_spanned(
+                        s,
+                        "Unsupported value for volatility attribute. Only immutable, stable, volatile are allowed.",
+                    ));
+                }
+                volatility = Some(s.value());
+            }
+            Ok(())
+        } else {
+            Err(syn::Error::new_spanned(
+                meta.path,
+                "Unknown attribute for UDF function",
+            ))
+        }
+    });
+    parser.parse2(input)?;
+    if let Some(expr) = &default {
+        if on_error.as_deref() != Some("default") {
+            return Err(syn::Error::new_spanned(
+                expr,
+                "The default value is only used with `on_error = \"default\"`.",
+            ));
+        }
+    }
+    _validate_function_names(name_lit.as_ref(), &alias_lits)?;
+    if let Some(since) = &since {
+        if deprecated.is_none() {
+            return Err(syn::Error::new_spanned(
+                since,
+                "The since version is only used with `deprecated = \"...\"`.",
+            ));
         }
     }
-    Ok((name, aliases, needs_context, can_return_errors, result_nullable))
+    Ok(UdfMeta {
+        name,
+        aliases,
+        needs_context,
+        can_return_errors,
+        result_nullable,
+        unchecked_types,
+        panic,
+        utf8,
+        types,
+        error_prefix,
+        on_error,
+        default: default.map(|expr| quote!(#expr).to_string()),
+        holder,
+        literal,
+        description,
+        examples,
+        volatility,
+        deprecated,
+        since: since.map(|since| since.value()),
+        deprecated_aliases,
+        namespace,
+        category,
+        shadow_builtin,
+        pc_name,
+        crate_name: None,
+    })
 }
 
-pub(crate) fn extract_params(input: proc_macro2::TokenStream) -> ItemFn {
-    syn::parse2(input).unwrap()
+// the name and aliases must be identifiers, and the aliases must differ from each other and from the name
+fn _validate_function_names(name: Option<&LitStr>, aliases: &[LitStr]) -> syn::Result<()> {
+    for lit in name.into_iter().chain(aliases) {
+        if !is_valid_function_name(&lit.value()) {
+            return Err(syn::Error::new_spanned(
+                lit,
+                format!(
+                    "Invalid function name `{}`, function names and aliases must be identifiers, e.g. \"my_func\".",
+                    lit.value()
+                ),
+            ));
+        }
+    }
+    for (index, alias) in aliases.iter().enumerate() {
+        if name.is_some_and(|name| name.value() == alias.value()) {
+            return Err(syn::Error::new_spanned(
+                alias,
+                format!("The alias `{}` is the same as the function name.", alias.value()),
+            ));
+        }
+        if aliases[..index].iter().any(|other| other.value() == alias.value()) {
+            return Err(syn::Error::new_spanned(
+                alias,
+                format!("The alias `{}` is specified more than once.", alias.value()),
+            ));
+        }
+    }
+    Ok(())
 }
 
-pub(crate) fn extract_result_type(return_type: &ReturnType) -> (ReturnType, bool) {
+// Extract the registry meta from the #[udf_registry(namespace = "net", category = "string")] macro attributes
+pub(crate) fn extract_registry_meta(input: proc_macro2::TokenStream) -> syn::Result<RegistryMeta> {
+    let mut registry_meta = RegistryMeta::default();
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("namespace") {
+            registry_meta.namespace = Some(_parse_namespace(&meta)?);
+            Ok(())
+        } else if meta.path.is_ident("category") {
+            let value = meta.value()?;
+            let s: LitStr = value.parse()?;
+            registry_meta.category = Some(s.value());
+            Ok(())
+        } else {
+            Err(syn::Error::new_spanned(
+                meta.path,
+                "Unknown attribute for UDF registry",
+            ))
+        }
+    });
+    parser.parse2(input)?;
+    Ok(registry_meta)
+}
+
+// parse the value of the `namespace` attribute, which is a non-empty name without dots, e.g. "net"
+fn _parse_namespace(meta: &ParseNestedMeta) -> syn::Result<String> {
+    let value = meta.value()?;
+    let s: LitStr = value.parse()?;
+    if s.value().is_empty() || s.value().contains('.') {
+        return Err(syn::Error::new_spanned(
+            s,
+            "The namespace must be a non-empty name without dots, e.g. \"net\".",
+        ));
+    }
+    Ok(s.value())
+}
+
+// Extract the description of the function from its `///` doc comments, which are `#[doc = "..."]` attributes,
+// the leading space of each line is removed like rustdoc does, and None is returned if there is no doc comment
+pub(crate) fn extract_doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value: Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(lit_str), .. }),
+                ..
+            }) => Some(lit_str.value()),
+            _ => None,
+        })
+        .flat_map(|doc| {
+            doc.split('\n')
+                .map(|line| line.strip_prefix(' ').unwrap_or(line).trim_end().to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let description = lines.join("\n").trim().to_string();
+    if description.is_empty() {
+        None
+    } else {
+        Some(description)
+    }
+}
+
+// parse the value of the `utf8` attribute, only error/lossy/unchecked are allowed
+fn _parse_utf8_policy(meta: &ParseNestedMeta) -> syn::Result<String> {
+    let value = meta.value()?;
+    let s: LitStr = value.parse()?;
+    if s.value() != "error" && s.value() != "lossy" && s.value() != "unchecked" {
+        return Err(syn::Error::new_spanned(
+            s,
+            "Unsupported value for utf8 attribute. Only error, lossy, unchecked are allowed.",
+        ));
+    }
+    Ok(s.value())
+}
+
+// Extract the parameter meta from the #[udf(utf8 = "lossy", literal)] attributes of a parameter,
+// `#[literal]` is a shorthand of `#[udf(literal)]`,
+// the attributes are removed from the parameter since they are only meaningful to the `udf` macro
+pub(crate) fn extract_param_meta(attrs: &mut Vec<Attribute>) -> syn::Result<ParamMeta> {
+    let param_meta = _parse_param_meta(attrs);
+    // the attributes are removed even if they are invalid, so that they are not left on the function
+    attrs.retain(|attr| !attr.path().is_ident("udf") && !attr.path().is_ident("literal"));
+    param_meta
+}
+
+fn _parse_param_meta(attrs: &[Attribute]) -> syn::Result<ParamMeta> {
+    let mut param_meta = ParamMeta::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("literal")) {
+        attr.meta.require_path_only()?;
+        param_meta.literal = true;
+    }
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("udf")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("utf8") {
+                param_meta.utf8 = Some(_parse_utf8_policy(&meta)?);
+                Ok(())
+            } else if meta.path.is_ident("literal") {
+                param_meta.literal = true;
+                Ok(())
+            } else {
+                Err(syn::Error::new_spanned(
+                    meta.path,
+                    "Unknown attribute for UDF parameter",
+                ))
+            }
+        })?;
+    }
+    Ok(param_meta)
+}
+
+pub(crate) fn extract_params(input: proc_macro2::TokenStream) -> syn::Result<ItemFn> {
+    syn::parse2(input)
+}
+
+pub(crate) fn extract_result_type(return_type: &ReturnType) -> syn::Result<(ReturnType, bool)> {
     let mut can_return_errors = false;
     let mut actual_return_type = return_type.clone();
 
@@ -99,25 +579,67 @@ pub(crate) fn extract_result_type(return_type: &ReturnType) -> (ReturnType, bool
             if let Some(last_segment) = path.path.segments.last() {
                 if last_segment.ident == "Result" {
                     match &last_segment.arguments {
-                        syn::PathArguments::AngleBracketed(angle_bracketed_param) => {
-                            // Result<X, E> has two parameters
-                            if angle_bracketed_param.args.len() == 2 {
-                                // get the actual type of `X`
-                                let success_type_arg = &angle_bracketed_param.args[0];
-                                let success_type = match success_type_arg {
-                                    GenericArgument::Type(ty) => ty,
-                                    _ => panic!("Expected a type for success_type_arg"),
-                                };
-                                can_return_errors = true;
-                                actual_return_type = ReturnType::Type(Default::default(), Box::new(success_type.clone()));
-                            }
+                        // Result<X, E> has two parameters
+                        syn::PathArguments::AngleBracketed(angle_bracketed_param)
+                            if angle_bracketed_param.args.len() == 2 =>
+                        {
+                            // get the actual type of `X`
+                            let success_type_arg = &angle_bracketed_param.args[0];
+                            let success_type = match success_type_arg {
+                                GenericArgument::Type(ty) => ty,
+                                _ => {
+                                    return Err(syn::Error::new_spanned(
+                                        success_type_arg,
+                                        "Expected a type for the success value of `Result`, e.g. `Result<i64, String>`.",
+                                    ))
+                                }
+                            };
+                            can_return_errors = true;
+                            actual_return_type = ReturnType::Type(Default::default(), Box::new(success_type.clone()));
                         }
                         // fail the compilation if the Result type is not well-formed
-                        _ => panic!("Result type is not well-formed and it is expected to have two generic parameters."),
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                last_segment,
+                                "Result type is not well-formed and it is expected to have two generic parameters, \
+                                e.g. `Result<i64, String>`.",
+                            ))
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok((actual_return_type, can_return_errors))
+}
+
+// return the inner type `T` if the given type is `Option<T>`, which is used for nullable parameters
+pub(crate) fn extract_option_type(ty: &Type) -> Option<&Type> {
+    if let Type::Path(path) = ty {
+        if let Some(last_segment) = path.path.segments.last() {
+            if last_segment.ident == "Option" {
+                if let syn::PathArguments::AngleBracketed(angle_bracketed_param) =
+                    &last_segment.arguments
+                {
+                    if let Some(GenericArgument::Type(inner_type)) =
+                        angle_bracketed_param.args.first()
+                    {
+                        return Some(inner_type);
                     }
                 }
             }
         }
     }
-    (actual_return_type, can_return_errors)
+    None
+}
+
+// unwrap the `Option<T>` result type of the function, the function returns NULL when the result is `None`
+// return a tuple of (actual_return_type, is_returning_nullable)
+pub(crate) fn extract_nullable_result_type(return_type: &ReturnType) -> (ReturnType, bool) {
+    if let ReturnType::Type(_, ref ty) = return_type {
+        if let Some(inner_type) = extract_option_type(ty) {
+            return (ReturnType::Type(Default::default(), Box::new(inner_type.clone())), true);
+        }
+    }
+    (return_type.clone(), false)
 }
@@ -4,108 +4,733 @@ mod attr_parser;
 
 extern crate proc_macro;
 
-use crate::attr_parser::{extract_params, extract_result_type, extract_udf_meta};
+use crate::attr_parser::{
+    extract_doc_comment, extract_nullable_result_type, extract_option_type, extract_param_meta,
+    extract_params, extract_registry_meta, extract_result_type, extract_udf_meta, UdfMeta,
+};
 use crate::quote_helper::{
-    function_wrapper_quote, is_returning_string, load_registered_udfs_quote, process_arg,
-    register_func_meta_quote, string_function_wrapper_quote,
+    compile_warning_quote, error_return_quote, function_wrapper_quote, holder_functions_quote,
+    is_returning_var_len, load_registered_udfs_quote, process_arg, register_func_meta_quote,
+    string_function_wrapper_quote,
+};
+use gandiva_rust_udf_common::{
+    decimal_precision_scale, is_context_type, is_context_value_type, is_gandiva_builtin,
+    is_string_writer_type, is_supported_param_type, is_supported_return_type, list_value_type,
+    map_type, strip_lifetimes, try_map_type, wrapper_function_name, wrapper_symbol_name,
+    SUPPORTED_PARAM_TYPES, SUPPORTED_RETURN_TYPES,
 };
 use quote::{format_ident, quote};
+use syn::visit_mut::VisitMut;
 use syn::{FnArg, ReturnType};
-use gandiva_rust_udf_common::map_type;
 
 #[proc_macro_attribute]
 pub fn udf_registry(
-    _attrs: proc_macro::TokenStream,
+    attrs: proc_macro::TokenStream,
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
     let input = proc_macro2::TokenStream::from(input);
-    udf_registry_impl(input).into()
+    udf_registry_impl(attrs.into(), input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
 
 
 fn udf_impl(
     input: proc_macro2::TokenStream,
-    name: Option<String>,
-    aliases: Vec<String>,
-    needs_context: bool,
-    mut _can_return_errors: bool,
-    result_nullable: Option<String>,
-) -> proc_macro2::TokenStream {
-    let function = extract_params(input);
-    let function_name = &function.sig.ident;
-    let (return_type, can_return_errors) = extract_result_type(&function.sig.output);
+    meta: UdfMeta,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let mut function = extract_params(input)?;
+    validate_signature(&function.sig)?;
+    let builtin_warning = validate_names(&function.sig.ident, &meta)?;
+    // the description specified by the attribute takes precedence over the doc comments
+    let mut meta = meta;
+    if meta.description.is_none() {
+        meta.description = extract_doc_comment(&function.attrs);
+    }
+    // the UTF-8 policy of each parameter, which is specified by the parameter or the function,
+    // and whether each parameter must be a literal
+    let mut utf8_policies = Vec::new();
+    let mut literal_params = Vec::new();
+    let mut param_names = Vec::new();
+    for input in function.sig.inputs.iter_mut() {
+        if let FnArg::Typed(pat_type) = input {
+            let param_meta = extract_param_meta(&mut pat_type.attrs)?;
+            let param_name = match pat_type.pat.as_ref() {
+                syn::Pat::Ident(pat_ident) => Some(pat_ident.ident.to_string()),
+                _ => None,
+            };
+            let is_literal = param_meta.literal
+                || param_name.as_ref().is_some_and(|name| meta.literal.contains(name));
+            utf8_policies.push(param_meta.utf8.or(meta.utf8.clone()).unwrap_or("error".to_string()));
+            literal_params.push(is_literal);
+            param_names.extend(param_name);
+        }
+    }
+    // the literal parameters named by the function attribute must exist
+    if let Some(unknown) = meta.literal.iter().find(|name| !param_names.contains(name)) {
+        return Err(syn::Error::new_spanned(
+            &function.sig,
+            format!("The literal parameter `{}` is not a parameter of the UDF.", unknown),
+        ));
+    }
+
+    // output the original function, and a wrapper along with its registration for each instantiation,
+    // non-generic functions have exactly one instantiation without type arguments
+    let mut expanded = quote! { #function #builtin_warning };
+    let mut wrapper_names = Vec::new();
+    let instantiations = type_instantiations(&function.sig, &meta.types)?;
+    // the exported symbol can only be specified for a single wrapper
+    if meta.pc_name.is_some() && instantiations.len() > 1 {
+        return Err(syn::Error::new_spanned(
+            &function.sig.generics,
+            "pc_name cannot be specified for generic UDFs with more than one instantiation.",
+        ));
+    }
+    for instantiation in instantiations {
+        let mut sig = function.sig.clone();
+        let mut substitution = TypeSubstitution(&instantiation);
+        for input in sig.inputs.iter_mut() {
+            substitution.visit_fn_arg_mut(input);
+        }
+        substitution.visit_return_type_mut(&mut sig.output);
+        let type_args = instantiation.iter().map(|(_, ty)| ty);
+        let function_name = &function.sig.ident;
+        let callee = if instantiation.is_empty() {
+            quote! { #function_name }
+        } else {
+            quote! { #function_name::<#(#type_args),*> }
+        };
+        let (wrapper_name, wrapper) =
+            udf_instance_impl(&sig, &callee, &meta, &utf8_policies, &literal_params)?;
+        // Gandiva resolves functions by their parameter types, so the instantiations must differ in them
+        if wrapper_names.contains(&wrapper_name) {
+            return Err(syn::Error::new_spanned(
+                &function.sig.generics,
+                format!(
+                    "Instantiations of generic UDFs must have different parameter types, \
+                    `{}` is generated more than once.",
+                    wrapper_name
+                ),
+            ));
+        }
+        wrapper_names.push(wrapper_name);
+        expanded.extend(wrapper);
+    }
+    Ok(expanded)
+}
+
+// generate the wrapper function and its registration for the function with the given (instantiated) signature,
+// return the wrapper name along with the generated code
+fn udf_instance_impl(
+    sig: &syn::Signature,
+    callee: &proc_macro2::TokenStream,
+    meta: &UdfMeta,
+    utf8_policies: &[String],
+    literal_params: &[bool],
+) -> syn::Result<(syn::Ident, proc_macro2::TokenStream)> {
+    let UdfMeta {
+        name,
+        aliases,
+        needs_context,
+        result_nullable,
+        unchecked_types,
+        panic,
+        error_prefix,
+        on_error,
+        default,
+        holder,
+        description,
+        examples,
+        volatility,
+        deprecated,
+        since,
+        deprecated_aliases,
+        namespace,
+        category,
+        pc_name,
+        crate_name,
+        ..
+    } = meta;
+    let mut result_nullable = result_nullable.clone();
+    let function_name = &sig.ident;
+    let (return_type, can_return_errors) = extract_result_type(&sig.output)?;
+    let (return_type, returns_nullable) = extract_nullable_result_type(&return_type);
+    // the function may write its string result into a `GdvStringWriter` parameter instead of returning it
+    let writes_string = sig.inputs.iter().any(|input| matches!(input, FnArg::Typed(pat_type) if is_string_writer_arg(pat_type)));
+    let return_type = if writes_string {
+        let returns_unit = match &return_type {
+            ReturnType::Default => true,
+            ReturnType::Type(_, ty) => matches!(ty.as_ref(), syn::Type::Tuple(tuple) if tuple.elems.is_empty()),
+        };
+        if !returns_unit {
+            return Err(syn::Error::new_spanned(
+                &sig.output,
+                "UDFs writing into `GdvStringWriter` must return `()`, `Option<()>` or `Result` of them.",
+            ));
+        }
+        ReturnType::Type(Default::default(), Box::new(syn::parse_quote!(String)))
+    } else {
+        return_type
+    };
 
     let mut wrapper_args = Vec::new();
     let mut call_args = Vec::new();
     let mut arg_types = Vec::new();
-    let is_returning_string = is_returning_string(&return_type);
-    let final_needs_context = needs_context || is_returning_string || can_return_errors;
+    let mut param_types = Vec::new();
+    let mut param_names = Vec::new();
+    let mut validity_checks = Vec::new();
+    let mut arg_decodes = Vec::new();
+    let is_returning_var_len = is_returning_var_len(&return_type);
+    // a panic in the function is reported as an error unless it is configured to abort
+    let catch_panic = panic.as_deref() != Some("abort");
+    // invalid UTF-8 in string parameters is reported as an error with the "error" policy
+    let checks_utf8 = sig
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Typed(pat_type) => Some(&pat_type.ty),
+            FnArg::Receiver(_) => None,
+        })
+        .zip(utf8_policies)
+        .any(|(ty, utf8_policy)| {
+            let value_type = extract_option_type(ty).unwrap_or(ty);
+            let value_type = quote!(#value_type).to_string();
+            let is_utf8 = map_type(&value_type) == "utf8" || list_value_type(&value_type).as_deref() == Some("utf8");
+            is_utf8 && utf8_policy == "error"
+        });
+    // decimal values not fitting into the decimal types of the parameters or the result are reported as errors
+    let checks_decimal = sig
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Typed(pat_type) => Some(&pat_type.ty),
+            FnArg::Receiver(_) => None,
+        })
+        .chain(match &return_type {
+            ReturnType::Type(_, ty) => Some(ty),
+            ReturnType::Default => None,
+        })
+        .any(|ty| map_type(&quote!(#ty).to_string()) == "decimal");
+    // an error returned from the function is reported unless it is configured to return NULL or a default value
+    let on_error = on_error.as_deref().unwrap_or("fail");
+    if on_error != "fail" && !can_return_errors {
+        return Err(syn::Error::new_spanned(
+            &sig.output,
+            format!("on_error = \"{}\" only applies to UDFs returning `Result`.", on_error),
+        ));
+    }
+    let default_value = default.as_deref().map(syn::parse_str::<syn::Expr>).transpose()?;
+    // the function may take the gandiva context as its first parameter, which can be used to report errors
+    let takes_context = matches!(
+        sig.inputs.first(),
+        Some(FnArg::Typed(pat_type)) if is_context_arg(pat_type)
+    );
+    let reports_errors =
+        (can_return_errors && on_error == "fail") || catch_panic || checks_utf8 || checks_decimal || takes_context;
+    let final_needs_context = *needs_context || is_returning_var_len || reports_errors;
+    let error_return = match &return_type {
+        ReturnType::Type(_, ty) => error_return_quote(ty),
+        ReturnType::Default => quote! {},
+    };
+
+    // functions with `Option` parameters or `Option` result handle NULL values internally
+    let nullable_arg = sig.inputs.iter().find_map(|input| match input {
+        FnArg::Typed(pat_type) if extract_option_type(&pat_type.ty).is_some() => Some(&pat_type.ty),
+        _ => None,
+    });
+    // errors are returned as NULL with `on_error = "null"`, which also handles NULL values internally
+    if on_error == "null" {
+        match result_nullable.as_deref() {
+            None | Some("internal") => result_nullable = Some("internal".to_string()),
+            Some(other) => {
+                return Err(syn::Error::new_spanned(
+                    &sig.output,
+                    format!(
+                        "Functions with on_error = \"null\" handle NULL internally, \
+                        result_nullable must be internal instead of {}.",
+                        other
+                    ),
+                ));
+            }
+        }
+    }
+    if nullable_arg.is_some() || returns_nullable {
+        match result_nullable.as_deref() {
+            None | Some("internal") => result_nullable = Some("internal".to_string()),
+            Some(other) => {
+                let message = format!(
+                    "Functions with `Option` parameters or `Option` result handle NULL internally, \
+                    result_nullable must be internal instead of {}.",
+                    other
+                );
+                return Err(match nullable_arg {
+                    Some(ty) => syn::Error::new_spanned(ty, message),
+                    None => syn::Error::new_spanned(&sig.output, message),
+                });
+            }
+        }
+    }
+    let null_internal = result_nullable.as_deref() == Some("internal");
 
     if final_needs_context {
         wrapper_args.push(quote! { ctx: i64 });
     }
+    // the holder made from the literal parameters is passed to the function as its first parameter,
+    // which follows the context parameter if any
+    let holder = holder.as_deref().map(syn::parse_str::<syn::Type>).transpose()?;
+    let holder_index = usize::from(takes_context);
+    if let Some(holder) = &holder {
+        let takes_holder = matches!(
+            sig.inputs.iter().nth(holder_index),
+            Some(FnArg::Typed(pat_type)) if is_holder_arg(pat_type, holder)
+        );
+        if !takes_holder {
+            return Err(syn::Error::new(
+                sig.paren_token.span.open(),
+                format!(
+                    "UDFs with a holder must take `&{}` as the first parameter.",
+                    quote!(#holder)
+                ),
+            ));
+        }
+        // the holder is made from the literal arguments, so a UDF without literal parameters has nothing to make it from
+        if !literal_params.contains(&true) {
+            return Err(syn::Error::new_spanned(
+                &sig.ident,
+                "UDFs with a holder must have literal parameters marked with `#[udf(literal)]`.",
+            ));
+        }
+        wrapper_args.push(quote! { holder_ptr: i64 });
+    }
 
-    for input in &function.sig.inputs {
+    let mut string_writer = None;
+    // whether each parameter of the gandiva function must be a literal
+    let mut literal_flags = Vec::new();
+    for (index, ((input, utf8_policy), is_literal)) in
+        sig.inputs.iter().zip(utf8_policies).zip(literal_params).enumerate()
+    {
+        if *is_literal {
+            if let FnArg::Typed(pat_type) = input {
+                let is_gdv_param = !is_context_arg(pat_type)
+                    && !is_string_writer_arg(pat_type)
+                    && !matches!(&holder, Some(holder) if is_holder_arg(pat_type, holder));
+                if !is_gdv_param {
+                    return Err(syn::Error::new_spanned(
+                        &pat_type.ty,
+                        "Only the parameters of the Gandiva function can be literals.",
+                    ));
+                }
+            }
+        }
         match input {
+            FnArg::Typed(pat_type) if is_context_arg(pat_type) => {
+                if index != 0 {
+                    return Err(syn::Error::new_spanned(
+                        &pat_type.ty,
+                        "`GdvContext` must be the first parameter of the UDF.",
+                    ));
+                }
+                call_args.push(quote! { &gdv_context });
+            }
+            FnArg::Typed(pat_type) if is_context_value_arg(pat_type) => {
+                return Err(syn::Error::new_spanned(
+                    &pat_type.ty,
+                    "`GdvContext` must be borrowed, take `&GdvContext` instead.",
+                ));
+            }
+            FnArg::Typed(pat_type) if matches!(&holder, Some(holder) if is_holder_arg(pat_type, holder)) => {
+                if index != holder_index {
+                    return Err(syn::Error::new_spanned(
+                        &pat_type.ty,
+                        "The holder must be the first parameter of the UDF.",
+                    ));
+                }
+                call_args.push(quote! {
+                    unsafe { gandiva_rust_udf_shared::udf_holder::<#holder>(holder_ptr) }
+                });
+            }
+            FnArg::Typed(pat_type) if is_string_writer_arg(pat_type) => {
+                let writer = match pat_type.pat.as_ref() {
+                    syn::Pat::Ident(pat_ident) => &pat_ident.ident,
+                    pat => {
+                        return Err(syn::Error::new_spanned(
+                            pat,
+                            "Expected an identifier for the `GdvStringWriter` parameter",
+                        ))
+                    }
+                };
+                if string_writer.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        &pat_type.ty,
+                        "UDFs can only take one `GdvStringWriter` parameter.",
+                    ));
+                }
+                arg_decodes.push(quote! {
+                    let mut #writer = gandiva_rust_udf_shared::GdvStringWriter::new(&gdv_context);
+                });
+                call_args.push(quote! { &mut #writer });
+                string_writer = Some(writer);
+            }
             FnArg::Typed(pat_type) => {
-                process_arg(pat_type, &mut wrapper_args, &mut call_args, &mut arg_types);
+                process_arg(
+                    pat_type,
+                    null_internal,
+                    utf8_policy,
+                    &error_return,
+                    &mut wrapper_args,
+                    &mut call_args,
+                    &mut arg_types,
+                    &mut param_types,
+                    &mut param_names,
+                    &mut validity_checks,
+                    &mut arg_decodes,
+                )?;
+                literal_flags.push(*is_literal);
             }
-            _ => {
-                panic!("Unsupported function argument type");
+            FnArg::Receiver(receiver) => {
+                return Err(syn::Error::new_spanned(
+                    receiver,
+                    "UDFs cannot take a `self` receiver, define the UDF as a free function instead.",
+                ));
             }
         }
     }
 
-    let wrapper_name = format_ident!("{}_{}", function_name, arg_types.join("_"));
+    // the context passed to the function and the string writer is borrowed from a local,
+    // so the memory allocated by it cannot outlive the call
+    if takes_context || string_writer.is_some() {
+        arg_decodes.insert(
+            0,
+            quote! { let gdv_context = unsafe { gandiva_rust_udf_shared::GdvContext::new(ctx) }; },
+        );
+    }
+
+    validate_types(sig, &return_type, *unchecked_types, holder.as_ref())?;
+
+    // a borrowed string result may point into one of the string/binary parameters
+    let borrowable_inputs: Vec<_> = sig
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Typed(pat_type) => match (pat_type.pat.as_ref(), &pat_type.ty) {
+                (syn::Pat::Ident(pat_ident), ty)
+                    if matches!(map_type(&quote!(#ty).to_string()).as_str(), "utf8" | "binary") =>
+                {
+                    Some(pat_ident.ident.clone())
+                }
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+
+    let wrapper_name = format_ident!("{}", wrapper_function_name(&function_name.to_string(), &arg_types));
+    let symbol_name = pc_name
+        .clone()
+        .unwrap_or_else(|| wrapper_symbol_name(crate_name.as_deref(), &wrapper_name.to_string()));
+    if null_internal {
+        wrapper_args.push(quote! { out_valid: *mut bool });
+    }
 
     let expanded = match return_type {
         ReturnType::Default => {
-            panic!("The function to be wrapped must have a return type.");
+            return Err(syn::Error::new(
+                sig.paren_token.span.close(),
+                "UDFs must return a value, add a return type such as `-> i64`.",
+            ));
         }
         ReturnType::Type(_, ty) => {
-            // if return type is String, use gandiva context function to allocate memory
+            // if return type is String or binary, use gandiva context function to allocate memory
             let return_type_str = quote!(#ty).to_string();
-            let mut return_arrow_type = map_type(return_type_str.as_str());
-            let wrapper_func = if return_type_str == "String" {
-                return_arrow_type = "utf8".to_string();
+            if decimal_precision_scale(&return_type_str).is_some() {
+                // gandiva passes the precision and scale of the result before its output parts
+                wrapper_args.push(quote! {
+                    out_precision: i32, out_scale: i32, out_high: *mut i64, out_low: *mut u64
+                });
+            }
+            let wrapper_func = if is_returning_var_len {
                 wrapper_args.push(quote! { out_len: *mut i32 });
                 string_function_wrapper_quote(
-                    &function,
                     &wrapper_name,
+                    &symbol_name,
                     &mut wrapper_args,
-                    &function_name,
-                    &mut call_args,
+                    callee,
+                    &call_args,
+                    &ty,
                     can_return_errors,
+                    returns_nullable,
+                    null_internal,
+                    &validity_checks,
+                    &arg_decodes,
+                    catch_panic,
+                    error_prefix.as_deref().unwrap_or_default(),
+                    on_error,
+                    default_value.as_ref(),
+                    string_writer,
+                    &borrowable_inputs,
                 )
             } else {
                 function_wrapper_quote(
-                    &function,
                     &wrapper_name,
+                    &symbol_name,
                     &mut wrapper_args,
-                    &function_name,
-                    &mut call_args,
+                    callee,
+                    &call_args,
                     &ty,
                     can_return_errors,
+                    returns_nullable,
+                    null_internal,
+                    &validity_checks,
+                    &arg_decodes,
+                    catch_panic,
+                    error_prefix.as_deref().unwrap_or_default(),
+                    on_error,
+                    default_value.as_ref(),
                 )
             };
             let register_func_meta = register_func_meta_quote(
-                &function_name,
-                &arg_types,
+                function_name,
+                &param_types,
+                &param_names,
                 &wrapper_name,
-                name,
-                aliases,
+                &symbol_name,
+                name.clone(),
+                aliases.clone(),
                 final_needs_context,
-                can_return_errors,
+                reports_errors,
                 result_nullable,
-                &return_arrow_type,
+                &return_type_str,
+                holder.is_some(),
+                &literal_flags,
+                description.as_deref(),
+                examples,
+                volatility.as_deref(),
+                deprecated.as_deref(),
+                since.as_deref(),
+                deprecated_aliases,
+                namespace.as_deref(),
+                category.as_deref(),
             );
+            let holder_functions = match &holder {
+                Some(holder) => holder_functions_quote(&wrapper_name, &symbol_name, holder),
+                None => quote! {},
+            };
+            // the volatility of functions without arguments is ambiguous, e.g. `pi()` and `random()`
+            let volatility_warning = if param_types.is_empty() && volatility.is_none() {
+                let note = format!(
+                    "`{}` takes no arguments, declare whether its result may change with \
+                    `#[udf(volatility = \"immutable\" | \"stable\" | \"volatile\")]` or `#[udf(deterministic = false)]`",
+                    function_name
+                );
+                compile_warning_quote(function_name, "VolatilityNotDeclared", &note)
+            } else {
+                quote! {}
+            };
             quote! {
                 #wrapper_func
+                #holder_functions
                 #register_func_meta
+                #volatility_warning
             }
         }
     };
-    expanded
+    Ok((wrapper_name, expanded))
+}
+
+// the aliases must differ from the function name, which may be the Rust function name, see `extract_udf_meta`
+// for the other checks, and the names shadowing Gandiva built-in functions are warned or rejected according to
+// the `shadow_builtin` attribute, return the tokens reporting the warning
+fn validate_names(function_name: &syn::Ident, meta: &UdfMeta) -> syn::Result<proc_macro2::TokenStream> {
+    let name = meta.name.clone().unwrap_or(function_name.to_string());
+    if meta.aliases.contains(&name) {
+        return Err(syn::Error::new_spanned(
+            function_name,
+            format!("The alias `{}` is the same as the function name.", name),
+        ));
+    }
+    let shadowing_names: Vec<&String> =
+        std::iter::once(&name).chain(&meta.aliases).filter(|name| is_gandiva_builtin(name)).collect();
+    if shadowing_names.is_empty() {
+        return Ok(quote! {});
+    }
+    let names = shadowing_names.iter().map(|name| format!("`{}`", name)).collect::<Vec<_>>().join(", ");
+    let shadowing = if shadowing_names.len() == 1 {
+        format!("name {} shadows a Gandiva built-in function", names)
+    } else {
+        format!("names {} shadow Gandiva built-in functions", names)
+    };
+    match meta.shadow_builtin.as_deref().unwrap_or("warn") {
+        "allow" => Ok(quote! {}),
+        "error" => Err(syn::Error::new_spanned(function_name, format!("The UDF {}.", shadowing))),
+        _ => {
+            let note = format!(
+                "the UDF {}, rename it or allow it with `#[udf(shadow_builtin = \"allow\")]`",
+                shadowing
+            );
+            Ok(compile_warning_quote(function_name, "BuiltinFunctionShadowed", &note))
+        }
+    }
+}
+
+// whether the parameter is the holder of the UDF, e.g. `holder: &RegexHolder` for `holder = RegexHolder`
+fn is_holder_arg(pat_type: &syn::PatType, holder: &syn::Type) -> bool {
+    let ty = &pat_type.ty;
+    let arg_type: String = strip_lifetimes(&quote!(#ty).to_string()).split_whitespace().collect();
+    let holder_type: String = quote!(#holder).to_string().split_whitespace().collect();
+    arg_type == format!("&{}", holder_type)
+}
+
+// whether the parameter is the gandiva context, e.g. `ctx: &GdvContext`
+fn is_context_arg(pat_type: &syn::PatType) -> bool {
+    let ty = &pat_type.ty;
+    is_context_type(&quote!(#ty).to_string())
+}
+
+// whether the parameter is the gandiva context taken by value, e.g. `ctx: GdvContext`
+fn is_context_value_arg(pat_type: &syn::PatType) -> bool {
+    let ty = &pat_type.ty;
+    is_context_value_type(&quote!(#ty).to_string())
+}
+
+// whether the parameter is the writer of the string result, e.g. `out: &mut GdvStringWriter`
+fn is_string_writer_arg(pat_type: &syn::PatType) -> bool {
+    let ty = &pat_type.ty;
+    is_string_writer_type(&quote!(#ty).to_string())
+}
+
+// reject function signatures which cannot be wrapped into a gandiva external function
+fn validate_signature(sig: &syn::Signature) -> syn::Result<()> {
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(syn::Error::new_spanned(
+            asyncness,
+            "UDFs cannot be async, remove the `async` keyword.",
+        ));
+    }
+    if let Some(variadic) = &sig.variadic {
+        return Err(syn::Error::new_spanned(
+            variadic,
+            "UDFs cannot be variadic, declare each parameter explicitly.",
+        ));
+    }
+    // lifetimes are allowed for borrowed parameters such as `&'a str`,
+    // and generic types are instantiated with the types specified by `types`, see `type_instantiations`
+    if let Some(param) = sig.generics.const_params().next() {
+        return Err(syn::Error::new_spanned(
+            param,
+            "Const generic UDFs are not supported, use concrete parameter types instead.",
+        ));
+    }
+    Ok(())
+}
+
+// return the type arguments of each instantiation of the function, which are all the combinations of the types
+// specified by the #[udf(types(T = [i32, i64]))] attribute, e.g. [[(T, i32)], [(T, i64)]]
+fn type_instantiations(
+    sig: &syn::Signature,
+    types: &[(String, Vec<String>)],
+) -> syn::Result<Vec<Vec<(syn::Ident, syn::Type)>>> {
+    for (type_param, _) in types {
+        if !sig.generics.type_params().any(|param| param.ident == type_param) {
+            return Err(syn::Error::new_spanned(
+                &sig.ident,
+                format!("`{}` in `types` is not a generic type parameter of the UDF.", type_param),
+            ));
+        }
+    }
+    let mut instantiations = vec![Vec::new()];
+    for param in sig.generics.type_params() {
+        let concrete_types = match types.iter().find(|(type_param, _)| param.ident == type_param) {
+            Some((_, concrete_types)) => concrete_types
+                .iter()
+                .map(|concrete_type| syn::parse_str::<syn::Type>(concrete_type))
+                .collect::<syn::Result<Vec<_>>>()?,
+            None => {
+                return Err(syn::Error::new_spanned(
+                    param,
+                    format!(
+                        "Generic UDFs must specify the types to instantiate `{0}` with, \
+                        e.g. `#[udf(types({0} = [i32, i64]))]`.",
+                        param.ident
+                    ),
+                ))
+            }
+        };
+        instantiations = instantiations
+            .into_iter()
+            .flat_map(|instantiation| {
+                concrete_types.iter().map(move |concrete_type| {
+                    let mut instantiation = instantiation.clone();
+                    instantiation.push((param.ident.clone(), concrete_type.clone()));
+                    instantiation
+                })
+            })
+            .collect();
+    }
+    Ok(instantiations)
+}
+
+// replace the generic type parameters with their type arguments, e.g. `Option<T>` ==> `Option<i64>`
+struct TypeSubstitution<'a>(&'a [(syn::Ident, syn::Type)]);
+
+impl VisitMut for TypeSubstitution<'_> {
+    fn visit_type_mut(&mut self, ty: &mut syn::Type) {
+        if let syn::Type::Path(syn::TypePath { qself: None, path }) = ty {
+            if let Some(ident) = path.get_ident() {
+                if let Some((_, concrete_type)) = self.0.iter().find(|(type_param, _)| type_param == ident) {
+                    *ty = concrete_type.clone();
+                    return;
+                }
+            }
+        }
+        syn::visit_mut::visit_type_mut(self, ty);
+    }
+}
+
+// reject parameter and return types which are not supported, unless `unchecked_types` is enabled,
+// in which case the unknown types are registered as custom types through `GdvType`
+fn validate_types(
+    sig: &syn::Signature,
+    return_type: &ReturnType,
+    unchecked_types: bool,
+    holder: Option<&syn::Type>,
+) -> syn::Result<()> {
+    let is_custom_type = |type_str: &str| unchecked_types && try_map_type(type_str).is_none();
+    let unsupported_type_error = |ty: &syn::Type, kind: &str, supported_types: &str| {
+        let type_str = quote!(#ty).to_string();
+        let mut message = format!(
+            "Unsupported {} type `{}`, supported types are {}.",
+            kind, type_str, supported_types
+        );
+        if try_map_type(&type_str).is_none() {
+            message.push_str(
+                " Custom types implementing `gandiva_rust_udf_shared::GdvType` are allowed \
+                with `#[udf(unchecked_types = true)]`.",
+            );
+        }
+        syn::Error::new_spanned(ty, message)
+    };
+    for input in &sig.inputs {
+        if let FnArg::Typed(pat_type) = input {
+            if matches!(holder, Some(holder) if is_holder_arg(pat_type, holder)) {
+                continue;
+            }
+            let ty = &pat_type.ty;
+            let arg_type = quote!(#ty).to_string();
+            if is_context_type(&arg_type) || is_string_writer_type(&arg_type) {
+                continue;
+            }
+            if !is_supported_param_type(&arg_type) && !is_custom_type(&arg_type) {
+                return Err(unsupported_type_error(ty, "parameter", SUPPORTED_PARAM_TYPES));
+            }
+        }
+    }
+    if let ReturnType::Type(_, ty) = return_type {
+        let return_type_str = quote!(#ty).to_string();
+        if !is_supported_return_type(&return_type_str) && !is_custom_type(&return_type_str) {
+            return Err(unsupported_type_error(ty, "return", SUPPORTED_RETURN_TYPES));
+        }
+    }
+    Ok(())
 }
 
 #[proc_macro_attribute]
@@ -113,20 +738,44 @@ pub fn udf(
     attrs: proc_macro::TokenStream,
     input: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
-    match extract_udf_meta(attrs.into()) {
-        Ok((name, aliases, needs_context, can_return_errors, result_nullable)) => {
-            let input = proc_macro2::TokenStream::from(input);
-            udf_impl(input, name, aliases, needs_context, can_return_errors, result_nullable).into()
+    let input = proc_macro2::TokenStream::from(input);
+    extract_udf_meta(attrs.into())
+        .map(|meta| UdfMeta {
+            crate_name: std::env::var("CARGO_CRATE_NAME").ok(),
+            ..meta
+        })
+        .and_then(|meta| udf_impl(input.clone(), meta))
+        .unwrap_or_else(|e| {
+            // keep the original function so that the error is not followed by unresolved references
+            let mut tokens = e.into_compile_error();
+            tokens.extend(strip_param_attrs(input));
+            tokens
+        })
+        .into()
+}
+
+// remove the `#[udf(...)]` and `#[literal]` parameter attributes from the function re-emitted with an error,
+// which would be reported as unknown attributes otherwise
+fn strip_param_attrs(input: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let mut function = match extract_params(input.clone()) {
+        Ok(function) => function,
+        Err(_) => return input,
+    };
+    for input in function.sig.inputs.iter_mut() {
+        if let FnArg::Typed(pat_type) = input {
+            let _ = extract_param_meta(&mut pat_type.attrs);
         }
-        Err(e) => syn::Error::new_spanned(e.to_compile_error(), e.to_string())
-            .to_compile_error()
-            .into(),
     }
+    quote!(#function)
 }
 
-fn udf_registry_impl(input: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
-    let function = extract_params(input);
-    load_registered_udfs_quote(function).into()
+fn udf_registry_impl(
+    attrs: proc_macro2::TokenStream,
+    input: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let registry_meta = extract_registry_meta(attrs)?;
+    let function = extract_params(input)?;
+    Ok(load_registered_udfs_quote(function, &registry_meta))
 }
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
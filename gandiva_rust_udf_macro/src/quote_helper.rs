@@ -1,7 +1,13 @@
 use proc_macro2::Ident;
 use quote::{format_ident, quote};
-use syn::{PatType, ReturnType, Type};
-use gandiva_rust_udf_common::map_type;
+use syn::ext::IdentExt;
+use syn::{Pat, PatIdent, PatType, ReturnType, Type, TypeReference, TypeSlice};
+use gandiva_rust_udf_common::{
+    decimal_precision_scale, fixed_size_binary_width, is_cow_str, is_var_len_type, list_value_type,
+    map_temporal_type, map_type, signature_type_name, strip_lifetimes, strip_option_type,
+    try_map_type,
+};
+use crate::attr_parser::{extract_option_type, RegistryMeta};
 
 fn _data_type_quote(type_name: &str) -> proc_macro2::TokenStream {
     quote! {
@@ -12,6 +18,60 @@ fn _data_type_quote(type_name: &str) -> proc_macro2::TokenStream {
     }
 }
 
+// the data type of a Rust parameter/result type, fixed size binary type also carries its byte width,
+// decimal type also carries its precision and scale, time/timestamp type also carries its unit,
+// and list type also carries its value type, custom type is registered with its `GdvType::TYPE_NAME`
+fn _rust_data_type_quote(rust_type_str: &str) -> proc_macro2::TokenStream {
+    let rust_type_str = strip_option_type(rust_type_str).unwrap_or(rust_type_str);
+    let type_name = map_type(rust_type_str);
+    if try_map_type(rust_type_str).is_none() {
+        // the type string is generated from the type tokens, so it can be parsed back
+        let custom_type: Type = syn::parse_str(rust_type_str).expect("Failed to parse custom type");
+        quote! {
+            gandiva_rust_udf_shared::DataType {
+                type_name: <#custom_type as gandiva_rust_udf_shared::GdvType>::TYPE_NAME.to_string(),
+                ..Default::default()
+            }
+        }
+    } else if let Some(byte_width) = fixed_size_binary_width(rust_type_str) {
+        quote! {
+            gandiva_rust_udf_shared::DataType {
+                type_name: #type_name.to_string(),
+                byte_width: Some(#byte_width),
+                ..Default::default()
+            }
+        }
+    } else if let Some((precision, scale)) = decimal_precision_scale(rust_type_str) {
+        quote! {
+            gandiva_rust_udf_shared::DataType {
+                type_name: #type_name.to_string(),
+                precision: Some(#precision),
+                scale: Some(#scale),
+                ..Default::default()
+            }
+        }
+    } else if let Some((_, Some(unit))) = map_temporal_type(rust_type_str) {
+        quote! {
+            gandiva_rust_udf_shared::DataType {
+                type_name: #type_name.to_string(),
+                unit: Some(#unit.to_string()),
+                ..Default::default()
+            }
+        }
+    } else if let Some(value_type) = list_value_type(rust_type_str) {
+        let value_type_quote = _data_type_quote(&value_type);
+        quote! {
+            gandiva_rust_udf_shared::DataType {
+                type_name: #type_name.to_string(),
+                value_type: Some(Box::new(#value_type_quote)),
+                ..Default::default()
+            }
+        }
+    } else {
+        _data_type_quote(&type_name)
+    }
+}
+
 fn _needs_context_quote(needs_context: bool) -> proc_macro2::TokenStream {
     if needs_context {
         quote! { needs_context: true, }
@@ -20,6 +80,98 @@ fn _needs_context_quote(needs_context: bool) -> proc_macro2::TokenStream {
     }
 }
 
+// the names of the C functions making and freeing the holder, see `holder_functions_quote`
+fn _holder_quote(symbol_name: &str, has_holder: bool) -> proc_macro2::TokenStream {
+    if has_holder {
+        let holder_maker = format!("{}_make_holder", symbol_name);
+        let holder_free = format!("{}_free_holder", symbol_name);
+        quote! {
+            holder_maker: Some(#holder_maker.to_string()),
+            holder_free: Some(#holder_free.to_string()),
+        }
+    } else {
+        quote! {}
+    }
+}
+
+fn _literal_params_quote(literal_params: &[bool]) -> proc_macro2::TokenStream {
+    if literal_params.contains(&true) {
+        quote! { literal_params: vec![#(#literal_params),*], }
+    } else {
+        quote! {}
+    }
+}
+
+fn _description_quote(description: Option<&str>, examples: &[String]) -> proc_macro2::TokenStream {
+    let description_quote = match description {
+        Some(description) => quote! { description: Some(#description.to_string()), },
+        None => quote! {},
+    };
+    let examples_quote = if examples.is_empty() {
+        quote! {}
+    } else {
+        quote! { examples: vec![#(#examples.to_string()),*], }
+    };
+    quote! { #description_quote #examples_quote }
+}
+
+fn _volatility_quote(volatility: Option<&str>) -> proc_macro2::TokenStream {
+    match volatility {
+        Some(volatility) if volatility != "immutable" => quote! { volatility: #volatility.to_string(), },
+        _ => quote! {},
+    }
+}
+
+fn _deprecation_quote(
+    deprecated: Option<&str>,
+    since: Option<&str>,
+    deprecated_aliases: &[String],
+) -> proc_macro2::TokenStream {
+    let deprecated_quote = match deprecated {
+        Some(deprecated) => quote! { deprecated: Some(#deprecated.to_string()), },
+        None => quote! {},
+    };
+    let since_quote = match since {
+        Some(since) => quote! { deprecated_since: Some(#since.to_string()), },
+        None => quote! {},
+    };
+    let deprecated_aliases_quote = if deprecated_aliases.is_empty() {
+        quote! {}
+    } else {
+        quote! { deprecated_aliases: vec![#(#deprecated_aliases.to_string()),*], }
+    };
+    quote! { #deprecated_quote #since_quote #deprecated_aliases_quote }
+}
+
+fn _namespace_quote(namespace: Option<&str>, category: Option<&str>) -> proc_macro2::TokenStream {
+    let namespace_quote = match namespace {
+        Some(namespace) => quote! { namespace: Some(#namespace.to_string()), },
+        None => quote! {},
+    };
+    let category_quote = match category {
+        Some(category) => quote! { category: Some(#category.to_string()), },
+        None => quote! {},
+    };
+    quote! { #namespace_quote #category_quote }
+}
+
+fn _option_str_quote(value: Option<&str>) -> proc_macro2::TokenStream {
+    match value {
+        Some(value) => quote! { Some(#value) },
+        None => quote! { None },
+    }
+}
+
+// the wrapper function is exported as the symbol, which differs from the function name if it is prefixed
+// with the crate name or specified by `pc_name`
+fn _export_attr_quote(function_name: &Ident, symbol_name: &str) -> proc_macro2::TokenStream {
+    if function_name == symbol_name {
+        quote! { #[no_mangle] }
+    } else {
+        quote! { #[export_name = #symbol_name] }
+    }
+}
+
 fn _can_return_errors_quote(can_return_errors: bool) -> proc_macro2::TokenStream {
     if can_return_errors {
         quote! { can_return_errors: true, }
@@ -36,70 +188,358 @@ fn _result_nullable_quote(result_nullable: Option<String>) -> proc_macro2::Token
     }
 }
 
+// for functions handling NULL internally, the result is NULL if any non `Option` parameter is NULL,
+// otherwise the result is marked as valid before calling the function
+fn _null_internal_quote(
+    null_internal: bool,
+    validity_checks: &[proc_macro2::TokenStream],
+    null_return: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if !null_internal {
+        return quote! {};
+    }
+    let null_check = if validity_checks.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            if !(#(#validity_checks)&&*) {
+                #null_return
+            }
+        }
+    };
+    quote! {
+        #null_check
+        unsafe {
+            *out_valid = true;
+        }
+    }
+}
+
+// the pattern matching a successful result of the function, e.g. `Ok(Some(value))` for `Result<Option<T>, E>`
+fn _result_value_pattern_quote(
+    value: proc_macro2::TokenStream,
+    can_return_errors: bool,
+    returns_nullable: bool,
+) -> proc_macro2::TokenStream {
+    let value = if returns_nullable {
+        quote! { Some(#value) }
+    } else {
+        value
+    };
+    if can_return_errors {
+        quote! { Ok(#value) }
+    } else {
+        value
+    }
+}
+
+// the pattern matching a NULL result of the function returning `Option<T>` or `Result<Option<T>, E>`
+fn _result_none_pattern_quote(can_return_errors: bool) -> proc_macro2::TokenStream {
+    if can_return_errors {
+        quote! { Ok(None) }
+    } else {
+        quote! { None }
+    }
+}
+
+// the statements returning from the wrapper function when an error is reported without a result value,
+// string/binary result returns a null pointer with zero length, decimal result has no value to return as
+// it is written into output parameters, bool result returns false, and other results return their default values
+pub(crate) fn error_return_quote(ty: &Type) -> proc_macro2::TokenStream {
+    let return_type_str = quote!(#ty).to_string();
+    if is_var_len_type(&map_type(&return_type_str)) {
+        quote! {
+            unsafe {
+                *out_len = 0;
+            }
+            return std::ptr::null_mut();
+        }
+    } else if decimal_precision_scale(&return_type_str).is_some() {
+        quote! { return; }
+    } else if return_type_str == "bool" {
+        quote! { return false; }
+    } else {
+        quote! { return Default::default(); }
+    }
+}
+
+// the error is only used when it is reported, i.e. `on_error` is "fail"
+fn _result_err_pattern_quote(on_error: &str) -> proc_macro2::TokenStream {
+    if on_error == "fail" {
+        quote! { Err(err) }
+    } else {
+        quote! { Err(_) }
+    }
+}
+
+// the value returned for an error with `on_error = "default"`, which is the `default` expression if specified
+fn _default_value_quote(default_value: Option<&syn::Expr>) -> proc_macro2::TokenStream {
+    match default_value {
+        Some(default_value) => quote! { #default_value },
+        None => quote! { Default::default() },
+    }
+}
+
+// call the function through `callee`, e.g. `my_udf` or `my_udf::<i64>` for generic functions,
+// if `catch_panic` is true, a panic in the function is caught instead of unwinding
+// across the FFI boundary, its message is reported through `set_error_msg` and `panic_return` is executed
+fn _call_quote(
+    callee: &proc_macro2::TokenStream,
+    call_args: &[proc_macro2::TokenStream],
+    catch_panic: bool,
+    panic_return: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if !catch_panic {
+        return quote! { #callee(#(#call_args),*) };
+    }
+    quote! {
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| #callee(#(#call_args),*))) {
+            Ok(result) => result,
+            Err(panic) => {
+                gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                #panic_return
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn string_function_wrapper_quote(
-    function: &syn::ItemFn,
     wrapper_name: &Ident,
+    symbol_name: &str,
     wrapper_args: &mut Vec<proc_macro2::TokenStream>,
-    function_name: &Ident,
-    call_args: &mut Vec<proc_macro2::TokenStream>,
+    callee: &proc_macro2::TokenStream,
+    call_args: &[proc_macro2::TokenStream],
+    ty: &Type,
     can_return_errors: bool,
+    returns_nullable: bool,
+    null_internal: bool,
+    validity_checks: &[proc_macro2::TokenStream],
+    arg_decodes: &[proc_macro2::TokenStream],
+    catch_panic: bool,
+    error_prefix: &str,
+    on_error: &str,
+    default_value: Option<&syn::Expr>,
+    string_writer: Option<&Ident>,
+    borrowable_inputs: &[Ident],
 ) -> proc_macro2::TokenStream {
-    let result_handling = if can_return_errors {
+    let null_internal_handling = _null_internal_quote(
+        null_internal,
+        validity_checks,
         quote! {
-            match result {
-                Ok(value) => gandiva_rust_udf_shared::return_gdv_string(ctx, &value, out_len),
-                Err(err) => {
-                    gandiva_rust_udf_shared::set_error_msg(ctx, &err);
-                    unsafe {
-                        *out_len = 0;
-                    }
-                    std::ptr::null_mut()
-                }
+            unsafe {
+                *out_valid = false;
+                *out_len = 0;
             }
-        }
+            return std::ptr::null_mut();
+        },
+    );
+    let call = _call_quote(callee, call_args, catch_panic, error_return_quote(ty));
+    // strings and binary data are both copied into memory allocated by gandiva context
+    let return_gdv_value = if map_type(&quote!(#ty).to_string()) == "utf8" {
+        quote! { gandiva_rust_udf_shared::return_gdv_string }
     } else {
+        quote! { gandiva_rust_udf_shared::return_gdv_bytes }
+    };
+    // a borrowed string result pointing into one of the string/binary parameters is returned without copying,
+    // the parameter pointers are captured before they are decoded
+    let returns_borrowed =
+        strip_lifetimes(&quote!(#ty).to_string()) == "& str" && !borrowable_inputs.is_empty();
+    let borrowable_inputs_capture = if returns_borrowed {
+        let input_lens = borrowable_inputs.iter().map(|input| format_ident!("{}_len", input));
         quote! {
-            gandiva_rust_udf_shared::return_gdv_string(ctx, &result, out_len)
+            let borrowable_inputs = [#((#borrowable_inputs as *const u8, #input_lens)),*];
         }
+    } else {
+        quote! {}
+    };
+    let return_gdv = |value: proc_macro2::TokenStream| {
+        if returns_borrowed {
+            quote! { gandiva_rust_udf_shared::return_gdv_borrowed_str(ctx, #value, &borrowable_inputs, out_len) }
+        } else {
+            quote! { #return_gdv_value(ctx, &#value, out_len) }
+        }
+    };
+    // the string written into `GdvStringWriter` is already in the memory allocated by gandiva context
+    let (value_pattern, return_value) = match string_writer {
+        Some(writer) => (
+            _result_value_pattern_quote(quote! { () }, can_return_errors, returns_nullable),
+            quote! { #writer.into_gdv_string(out_len) },
+        ),
+        None => (
+            _result_value_pattern_quote(quote! { value }, can_return_errors, returns_nullable),
+            return_gdv(quote! { value }),
+        ),
+    };
+    let mut result_arms = vec![quote! {
+        #value_pattern => #return_value
+    }];
+    if returns_nullable {
+        let none_pattern = _result_none_pattern_quote(can_return_errors);
+        result_arms.push(quote! {
+            #none_pattern => {
+                unsafe {
+                    *out_valid = false;
+                    *out_len = 0;
+                }
+                std::ptr::null_mut()
+            }
+        });
+    }
+    if can_return_errors {
+        let error_handling = match on_error {
+            "null" => quote! {
+                unsafe {
+                    *out_valid = false;
+                    *out_len = 0;
+                }
+                std::ptr::null_mut()
+            },
+            "default" => {
+                // the default expression is converted into the result type, e.g. `default = "n/a"` for `String`
+                let default_value = match default_value {
+                    Some(default_value) => quote! { (#default_value).into() },
+                    None => _default_value_quote(None),
+                };
+                let return_default_value = return_gdv(quote! { default_value });
+                // the lifetimes of a borrowed result are not declared in the wrapper function
+                let default_type = strip_lifetimes(&quote!(#ty).to_string())
+                    .parse::<proc_macro2::TokenStream>()
+                    .unwrap_or_else(|_| quote! { #ty });
+                quote! {
+                    let default_value: #default_type = #default_value;
+                    #return_default_value
+                }
+            }
+            _ => quote! {
+                gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::error_message(#error_prefix, &err));
+                unsafe {
+                    *out_len = 0;
+                }
+                std::ptr::null_mut()
+            },
+        };
+        let err_pattern = _result_err_pattern_quote(on_error);
+        result_arms.push(quote! {
+            #err_pattern => {
+                #error_handling
+            }
+        });
+    }
+    let (call_statement, result_handling) = if can_return_errors || returns_nullable {
+        (
+            quote! { let result = #call; },
+            quote! {
+                match result {
+                    #(#result_arms),*
+                }
+            },
+        )
+    } else if let Some(writer) = string_writer {
+        // the function returns `()` when writing into `GdvStringWriter`
+        (quote! { #call; }, quote! { #writer.into_gdv_string(out_len) })
+    } else {
+        (
+            quote! { let result = #call; },
+            return_gdv(quote! { result }),
+        )
     };
 
+    let export_attr = _export_attr_quote(wrapper_name, symbol_name);
+    // the output parameters are written through raw pointers passed by gandiva, which is allowed
+    // for the generated wrapper instead of failing clippy in the crates defining UDFs
     quote! {
-        // output the original function
-        #function
-
-        #[no_mangle]
+        #export_attr
+        #[allow(clippy::not_unsafe_ptr_arg_deref)]
         pub extern "C" fn #wrapper_name(#(#wrapper_args),*) -> *mut libc::c_char {
-            let result = #function_name(#(#call_args),*);
+            #null_internal_handling
+            #borrowable_inputs_capture
+            #(#arg_decodes)*
+            #call_statement
             #result_handling
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn function_wrapper_quote(
-    function: &syn::ItemFn,
     wrapper_name: &Ident,
+    symbol_name: &str,
     wrapper_args: &mut Vec<proc_macro2::TokenStream>,
-    function_name: &Ident,
-    call_args: &mut Vec<proc_macro2::TokenStream>,
-    ty: &Box<Type>,
+    callee: &proc_macro2::TokenStream,
+    call_args: &[proc_macro2::TokenStream],
+    ty: &Type,
     can_return_errors: bool,
+    returns_nullable: bool,
+    null_internal: bool,
+    validity_checks: &[proc_macro2::TokenStream],
+    arg_decodes: &[proc_macro2::TokenStream],
+    catch_panic: bool,
+    error_prefix: &str,
+    on_error: &str,
+    default_value: Option<&syn::Expr>,
 ) -> proc_macro2::TokenStream {
+    let null_internal_handling = _null_internal_quote(
+        null_internal,
+        validity_checks,
+        quote! {
+            unsafe {
+                *out_valid = false;
+            }
+            return Default::default();
+        },
+    );
+
+    let return_type_str = quote!(#ty).to_string();
     // if error occurs, set error message and return default value
-    // if return type is bool, return false, else return 0 (and converted into corresponding type)
-    let default_return_value = if quote!(#ty).to_string() == "bool" {
+    // if return type is bool, return false, else return the default value of the type, e.g. 0 for integers
+    let is_returning_decimal = decimal_precision_scale(&return_type_str).is_some();
+    let default_return_value = if return_type_str == "bool" {
         quote! { false }
     } else {
-        quote! { 0.into() }
+        quote! { Default::default() }
     };
+    let call = _call_quote(callee, call_args, catch_panic, error_return_quote(ty));
 
-    let result_handling = if can_return_errors {
+    let value_pattern =
+        _result_value_pattern_quote(quote! { return_value }, can_return_errors, returns_nullable);
+    let mut result_arms = vec![quote! { #value_pattern => return_value }];
+    if returns_nullable {
+        let none_pattern = _result_none_pattern_quote(can_return_errors);
+        result_arms.push(quote! {
+            #none_pattern => {
+                unsafe {
+                    *out_valid = false;
+                }
+                Default::default()
+            }
+        });
+    }
+    if can_return_errors {
+        let error_handling = match on_error {
+            "null" => quote! {
+                unsafe {
+                    *out_valid = false;
+                }
+                #default_return_value
+            },
+            "default" => _default_value_quote(default_value),
+            _ => quote! {
+                gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::error_message(#error_prefix, &err));
+                #default_return_value
+            },
+        };
+        let err_pattern = _result_err_pattern_quote(on_error);
+        result_arms.push(quote! {
+            #err_pattern => {
+                #error_handling
+            }
+        });
+    }
+    let result_handling = if can_return_errors || returns_nullable {
         quote! {
             match result {
-                Ok(return_value) => return_value,
-                Err(err) => {
-                    gandiva_rust_udf_shared::set_error_msg(ctx, &err);
-                    #default_return_value
-                }
+                #(#result_arms),*
             }
         }
     } else {
@@ -108,52 +548,103 @@ pub(crate) fn function_wrapper_quote(
         }
     };
 
-    quote! {
-      // output the original function
-      #function
+    // decimal result is returned through the `out_high` and `out_low` output parameters
+    // in the precision and scale given by `out_precision` and `out_scale`, a result not fitting into them is an error
+    let (wrapper_return_type, result_handling) = if is_returning_decimal {
+        (
+            quote! {},
+            quote! {
+                let return_value: #ty = #result_handling;
+                let (high, low) = match return_value.to_gdv_parts(out_precision, out_scale) {
+                    Ok(parts) => parts,
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &format!("Invalid decimal result: {}", err));
+                        (0, 0)
+                    }
+                };
+                unsafe {
+                    *out_high = high;
+                    *out_low = low;
+                }
+            },
+        )
+    } else {
+        (quote! { -> #ty }, result_handling)
+    };
 
-      #[no_mangle]
-      pub extern "C" fn #wrapper_name(#(#wrapper_args),*) -> #ty {
-        let result = #function_name(#(#call_args),*);
+    let export_attr = _export_attr_quote(wrapper_name, symbol_name);
+    // see `string_function_wrapper_quote` for the allowed lint
+    quote! {
+      #export_attr
+      #[allow(clippy::not_unsafe_ptr_arg_deref)]
+      pub extern "C" fn #wrapper_name(#(#wrapper_args),*) #wrapper_return_type {
+        #null_internal_handling
+        #(#arg_decodes)*
+        let result = #call;
         #result_handling
       }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn register_func_meta_quote(
     function_name: &Ident,
-    arg_types: &Vec<String>,
+    param_types: &[proc_macro2::TokenStream],
+    param_names: &[String],
     wrapper_name: &Ident,
+    symbol_name: &str,
     name: Option<String>,
     aliases: Vec<String>,
     needs_context: bool,
     can_return_errors: bool,
     result_nullable: Option<String>,
-    return_arrow_type: &str,
+    return_type_str: &str,
+    has_holder: bool,
+    literal_params: &[bool],
+    description: Option<&str>,
+    examples: &[String],
+    volatility: Option<&str>,
+    deprecated: Option<&str>,
+    since: Option<&str>,
+    deprecated_aliases: &[String],
+    namespace: Option<&str>,
+    category: Option<&str>,
 ) -> proc_macro2::TokenStream {
     let base_name_str = name.unwrap_or(function_name.to_string());
-    let arg_types_quotes = arg_types.iter().map(|arg_type| _data_type_quote(arg_type));
     let aliases_quotes = aliases.iter().map(|alias| quote! { #alias.to_string() });
-    let pc_name_str = wrapper_name.to_string();
+    let pc_name_str = symbol_name;
     // register the wrapper function metadata
     let register_func_ident = format_ident!("register_{}", wrapper_name);
-    let return_type_quote = _data_type_quote(return_arrow_type);
+    let return_type_quote = _rust_data_type_quote(return_type_str);
 
     let result_nullable_quote = _result_nullable_quote(result_nullable);
 
     let needs_context_quote = _needs_context_quote(needs_context);
     let can_return_errors_quote = _can_return_errors_quote(can_return_errors);
+    let holder_quote = _holder_quote(symbol_name, has_holder);
+    let literal_params_quote = _literal_params_quote(literal_params);
+    let description_quote = _description_quote(description, examples);
+    let volatility_quote = _volatility_quote(volatility);
+    let deprecation_quote = _deprecation_quote(deprecated, since, deprecated_aliases);
+    let namespace_quote = _namespace_quote(namespace, category);
     let register_func_meta = quote! {
         pub fn #register_func_ident() {
             gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
                 name: #base_name_str.to_string(),
                 aliases: vec![#(#aliases_quotes),*],
-                param_types: vec![#(#arg_types_quotes),*],
+                param_types: vec![#(#param_types),*],
+                param_names: vec![#(#param_names.to_string()),*],
                 return_type: #return_type_quote,
                 pc_name: #pc_name_str.to_string(),
                 #result_nullable_quote
                 #needs_context_quote
                 #can_return_errors_quote
+                #holder_quote
+                #literal_params_quote
+                #description_quote
+                #volatility_quote
+                #deprecation_quote
+                #namespace_quote
                 ..Default::default()
             });
         }
@@ -161,49 +652,301 @@ pub(crate) fn register_func_meta_quote(
     register_func_meta
 }
 
-pub(crate) fn is_returning_string(return_type: &ReturnType) -> bool {
-    match return_type {
-        ReturnType::Default => {
-            panic!("The function to be wrapped must have a return type.");
+// the C functions making the holder from the literal arguments and freeing it, which are called by the library loader
+pub(crate) fn holder_functions_quote(wrapper_name: &Ident, symbol_name: &str, holder: &Type) -> proc_macro2::TokenStream {
+    let holder_maker = format_ident!("{}_make_holder", wrapper_name);
+    let holder_free = format_ident!("{}_free_holder", wrapper_name);
+    let holder_maker_attr = _export_attr_quote(&holder_maker, &format!("{}_make_holder", symbol_name));
+    let holder_free_attr = _export_attr_quote(&holder_free, &format!("{}_free_holder", symbol_name));
+    // the pointers are passed by gandiva, see `string_function_wrapper_quote` for the allowed lint
+    quote! {
+        #holder_maker_attr
+        #[allow(clippy::not_unsafe_ptr_arg_deref)]
+        pub extern "C" fn #holder_maker(
+            literals: *const *const libc::c_char,
+            literal_lens: *const i32,
+            literal_count: i32,
+            error_msg: *mut *mut libc::c_char,
+        ) -> i64 {
+            unsafe {
+                gandiva_rust_udf_shared::make_udf_holder::<#holder>(literals, literal_lens, literal_count, error_msg)
+            }
         }
+
+        #holder_free_attr
+        pub extern "C" fn #holder_free(holder: i64) {
+            unsafe {
+                gandiva_rust_udf_shared::free_udf_holder::<#holder>(holder)
+            }
+        }
+    }
+}
+
+// proc macros cannot emit warnings on stable Rust, so the warning is reported by using a deprecated item
+// named `warning_name`, which is pointed at the function name
+pub(crate) fn compile_warning_quote(function_name: &Ident, warning_name: &str, note: &str) -> proc_macro2::TokenStream {
+    let warning = format_ident!("{}", warning_name);
+    let warning_usage = format_ident!("{}", warning_name, span = function_name.span());
+    let usage = quote::quote_spanned! { function_name.span() =>
+        let _ = #warning_usage;
+    };
+    quote! {
+        const _: () = {
+            #[deprecated(note = #note)]
+            struct #warning;
+            #usage
+        };
+    }
+}
+
+pub(crate) fn is_returning_var_len(return_type: &ReturnType) -> bool {
+    match return_type {
+        // functions without return type are rejected by `udf_impl`
+        ReturnType::Default => false,
         ReturnType::Type(_, ty) => {
-            // if return type is String, use gandiva context function to allocate memory
+            // if return type is String or binary, use gandiva context function to allocate memory
             let return_type_str = quote!(#ty).to_string();
-            if return_type_str == "String" {
-                return true;
+            is_var_len_type(&map_type(&return_type_str))
+        }
+    }
+}
+
+// when the function handles NULL internally (`with_validity` is true), Gandiva passes a validity
+// flag after each parameter, `Option<T>` parameters receive `None` for NULL values, and the names
+// of the validity flags for other parameters are collected into `validity_checks`
+// string parameters are decoded according to `utf8_policy`, with the "error" policy they are decoded
+// by the statements collected into `arg_decodes` before calling the function, which execute `error_return`
+// for invalid UTF-8
+// decode the parameter with `decode` returning a `Result`, the error is reported with `error_msg`
+// before calling the function, and a NULL `Option` parameter is not decoded
+fn _checked_decode_quote(
+    arg_name: &Ident,
+    is_nullable: bool,
+    decode: proc_macro2::TokenStream,
+    value: proc_macro2::TokenStream,
+    error_msg: &str,
+    error_return: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let arg_name_valid = format_ident!("{}_valid", quote!(#arg_name).to_string());
+    let decode = quote! {
+        match #decode {
+            Ok(value) => #value,
+            Err(err) => {
+                gandiva_rust_udf_shared::set_error_msg(ctx, &format!(#error_msg, err));
+                #error_return
             }
         }
     };
-    false
+    let decode = if is_nullable {
+        quote! { if #arg_name_valid { Some(#decode) } else { None } }
+    } else {
+        decode
+    };
+    quote! { let #arg_name = #decode; }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn process_arg(
     PatType { ty, pat, .. }: &PatType,
+    with_validity: bool,
+    utf8_policy: &str,
+    error_return: &proc_macro2::TokenStream,
     wrapper_args: &mut Vec<proc_macro2::TokenStream>,
     call_args: &mut Vec<proc_macro2::TokenStream>,
     arg_types: &mut Vec<String>,
-) {
-    let arg_name = pat;
+    param_types: &mut Vec<proc_macro2::TokenStream>,
+    param_names: &mut Vec<String>,
+    validity_checks: &mut Vec<proc_macro2::TokenStream>,
+    arg_decodes: &mut Vec<proc_macro2::TokenStream>,
+) -> syn::Result<()> {
+    // the parameter name is used to name the wrapper parameters, e.g. `x_len` and `x_valid`
+    let arg_name = match pat.as_ref() {
+        Pat::Ident(PatIdent { ident, by_ref: None, subpat: None, .. }) => ident,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                pat,
+                "UDF parameters must be plain identifiers, e.g. `x: i64`.",
+            ))
+        }
+    };
+    // the registered parameter name omits the raw identifier prefix and the leading underscores of unused parameters
+    let param_name = arg_name.unraw().to_string();
+    let param_name = match param_name.trim_start_matches('_') {
+        "" => param_name.clone(),
+        trimmed => trimmed.to_string(),
+    };
+    param_names.push(param_name);
+    let nullable_inner_type = extract_option_type(ty);
+    let value_type = nullable_inner_type.unwrap_or(ty);
     let arg_type = quote!(#ty).to_string();
     // if arg_type is ["i8" | "i16" | "i32" | "i64"] ==> ["int_8" | "int_16" | "int_32" | "int_64"]
     let arg_type_str = arg_type.as_str();
     let mapped_gdv_arg_type = map_type(arg_type_str);
 
-    if mapped_gdv_arg_type == "utf8" {
-        let arg_name_len = format_ident!("{}_len", quote!(#arg_name).to_string());
+    let arg_name_len = format_ident!("{}_len", quote!(#arg_name).to_string());
+    let arg_name_valid = format_ident!("{}_valid", quote!(#arg_name).to_string());
+    let mut is_decoded = false;
+    let arg_value = if mapped_gdv_arg_type == "utf8" {
         wrapper_args.push(quote! { #arg_name: *const libc::c_char, #arg_name_len: i32 });
-        call_args.push(quote! { std::str::from_utf8(
-            unsafe { std::slice::from_raw_parts(#arg_name as *const u8, #arg_name_len as usize) }
-        ).unwrap() });
+        let slice = quote! { std::slice::from_raw_parts(#arg_name as *const u8, #arg_name_len as usize) };
+        let bytes = quote! { unsafe { #slice } };
+        let is_cow = is_cow_str(&quote!(#value_type).to_string());
+        match utf8_policy {
+            "lossy" if is_cow => quote! { String::from_utf8_lossy(#bytes) },
+            "lossy" => {
+                return Err(syn::Error::new_spanned(
+                    value_type,
+                    "UTF-8 lossy parameters receive a `Cow<str>`, declare the parameter as `Cow<str>` instead.",
+                ))
+            }
+            "unchecked" => {
+                let value = quote! { unsafe { std::str::from_utf8_unchecked(#slice) } };
+                if is_cow {
+                    quote! { std::borrow::Cow::Borrowed(#value) }
+                } else {
+                    value
+                }
+            }
+            _ => {
+                // invalid UTF-8 is reported as an error before calling the function
+                let error_msg = format!("Invalid UTF-8 in parameter `{}`: {{}}", arg_name);
+                let value = if is_cow {
+                    quote! { std::borrow::Cow::Borrowed(value) }
+                } else {
+                    quote! { value }
+                };
+                arg_decodes.push(_checked_decode_quote(
+                    arg_name,
+                    nullable_inner_type.is_some(),
+                    quote! { std::str::from_utf8(#bytes) },
+                    value,
+                    &error_msg,
+                    error_return,
+                ));
+                is_decoded = true;
+                quote! { #arg_name }
+            }
+        }
+    } else if mapped_gdv_arg_type == "binary" {
+        // binary data is passed as is without UTF-8 validation
+        wrapper_args.push(quote! { #arg_name: *const libc::c_char, #arg_name_len: i32 });
+        quote! { unsafe { std::slice::from_raw_parts(#arg_name as *const u8, #arg_name_len as usize) } }
+    } else if mapped_gdv_arg_type == "decimal" {
+        // decimal is passed as its high and low 64 bits along with its precision and scale
+        let arg_name_str = quote!(#arg_name).to_string();
+        let arg_name_high = format_ident!("{}_high", arg_name_str);
+        let arg_name_low = format_ident!("{}_low", arg_name_str);
+        let arg_name_precision = format_ident!("{}_precision", arg_name_str);
+        let arg_name_scale = format_ident!("{}_scale", arg_name_str);
+        wrapper_args.push(quote! {
+            #arg_name_high: i64, #arg_name_low: u64, #arg_name_precision: i32, #arg_name_scale: i32
+        });
+        // a value not fitting into the decimal type of the parameter is reported as an error before calling the function
+        let error_msg = format!("Invalid decimal in parameter `{}`: {{}}", arg_name);
+        arg_decodes.push(_checked_decode_quote(
+            arg_name,
+            nullable_inner_type.is_some(),
+            quote! {
+                gandiva_rust_udf_shared::Decimal128::from_gdv_parts(
+                    #arg_name_high, #arg_name_low, #arg_name_precision, #arg_name_scale
+                )
+            },
+            quote! { value },
+            &error_msg,
+            error_return,
+        ));
+        is_decoded = true;
+        quote! { #arg_name }
+    } else if mapped_gdv_arg_type == "list" {
+        // list is passed as the pointer to its values and the number of values,
+        // and string list is passed as the data buffer, the offsets and the number of strings
+        match value_type {
+            Type::Reference(TypeReference { elem, .. }) => match elem.as_ref() {
+                Type::Slice(TypeSlice { elem, .. }) => {
+                    wrapper_args.push(quote! { #arg_name: *const #elem, #arg_name_len: i32 });
+                    quote! { unsafe { std::slice::from_raw_parts(#arg_name, #arg_name_len as usize) } }
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        value_type,
+                        "Unsupported list parameter type, expected `&[T]` or `StrList`.",
+                    ))
+                }
+            },
+            _ => {
+                let arg_name_offsets = format_ident!("{}_offsets", quote!(#arg_name).to_string());
+                wrapper_args.push(quote! {
+                    #arg_name: *const libc::c_char, #arg_name_offsets: *const i32, #arg_name_len: i32
+                });
+                match utf8_policy {
+                    "unchecked" => quote! {
+                        unsafe { gandiva_rust_udf_shared::StrList::from_gdv_parts(#arg_name, #arg_name_offsets, #arg_name_len) }
+                    },
+                    "lossy" => {
+                        return Err(syn::Error::new_spanned(
+                            value_type,
+                            "UTF-8 lossy is not supported for `StrList`, \
+                            use `#[udf(utf8 = \"error\")]` or `#[udf(utf8 = \"unchecked\")]` for the parameter instead.",
+                        ))
+                    }
+                    _ => {
+                        // invalid UTF-8 in any string is reported as an error before calling the function
+                        let error_msg = format!("Invalid UTF-8 in parameter `{}`: {{}}", arg_name);
+                        arg_decodes.push(_checked_decode_quote(
+                            arg_name,
+                            nullable_inner_type.is_some(),
+                            quote! {
+                                unsafe {
+                                    gandiva_rust_udf_shared::StrList::from_gdv_parts_checked(
+                                        #arg_name, #arg_name_offsets, #arg_name_len
+                                    )
+                                }
+                            },
+                            quote! { value },
+                            &error_msg,
+                            error_return,
+                        ));
+                        is_decoded = true;
+                        quote! { #arg_name }
+                    }
+                }
+            }
+        }
     } else {
-        wrapper_args.push(quote! { #arg_name: #ty });
-        call_args.push(quote! { #arg_name });
+        wrapper_args.push(quote! { #arg_name: #value_type });
+        quote! { #arg_name }
+    };
+
+    if with_validity {
+        wrapper_args.push(quote! { #arg_name_valid: bool });
+    }
+    if nullable_inner_type.is_some() && is_decoded {
+        // the decoded `Option` parameter is already `None` for NULL values
+        call_args.push(arg_value);
+    } else if nullable_inner_type.is_some() {
+        call_args.push(quote! { if #arg_name_valid { Some(#arg_value) } else { None } });
+    } else {
+        if with_validity {
+            validity_checks.push(quote! { #arg_name_valid });
+        }
+        call_args.push(arg_value);
     }
-    arg_types.push(mapped_gdv_arg_type);
+    param_types.push(_rust_data_type_quote(arg_type_str));
+    arg_types.push(signature_type_name(arg_type_str));
+    Ok(())
 }
 
-pub(crate) fn load_registered_udfs_quote(function: syn::ItemFn) -> proc_macro2::TokenStream {
+pub(crate) fn load_registered_udfs_quote(function: syn::ItemFn, registry_meta: &RegistryMeta) -> proc_macro2::TokenStream {
     let registry_function_name = &function.sig.ident;
+    // the registry defaults are applied to the UDFs not specifying them
+    let defaults_quote = match registry_meta {
+        RegistryMeta { namespace: None, category: None } => quote! {},
+        RegistryMeta { namespace, category } => {
+            let namespace = _option_str_quote(namespace.as_deref());
+            let category = _option_str_quote(category.as_deref());
+            quote! { gandiva_rust_udf_shared::apply_udf_registry_defaults(#namespace, #category); }
+        }
+    };
 
     quote! {
         #function
@@ -211,6 +954,7 @@ pub(crate) fn load_registered_udfs_quote(function: syn::ItemFn) -> proc_macro2::
         #[no_mangle]
         pub extern "C" fn load_registered_udfs() -> *mut libc::c_char {
             #registry_function_name();
+            #defaults_quote
             let registry_c_str = gandiva_rust_udf_shared::get_udf_registry();
             registry_c_str
         }
@@ -219,5 +963,14 @@ pub(crate) fn load_registered_udfs_quote(function: syn::ItemFn) -> proc_macro2::
         pub extern "C" fn finish_loading_registered_udfs(registry: *mut libc::c_char) {
             gandiva_rust_udf_shared::free_udf_registry(registry);
         }
+
+        // release the error message of a failed holder maker, see `make_udf_holder`
+        #[no_mangle]
+        #[allow(clippy::not_unsafe_ptr_arg_deref)]
+        pub extern "C" fn free_udf_holder_error(error_msg: *mut libc::c_char) {
+            unsafe {
+                gandiva_rust_udf_shared::free_udf_holder_error(error_msg);
+            }
+        }
     }
 }
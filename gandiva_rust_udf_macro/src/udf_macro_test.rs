@@ -1,10 +1,14 @@
 #[cfg(test)]
 mod macro_tests {
+    use crate::attr_parser::UdfMeta;
     use crate::extract_udf_meta;
+    use crate::strip_param_attrs;
     use crate::udf_impl;
     use crate::udf_registry_impl;
     use default_args::default_args;
 
+    // the wrappers are generated with the default options, e.g. catching panics, so that the expected
+    // code is what the UDFs get by default
     default_args! {
         fn gen_udf(
             input: proc_macro2::TokenStream,
@@ -14,10 +18,87 @@ mod macro_tests {
             can_return_errors: bool = false,
             result_nullable: Option<String> = None,
         ) -> proc_macro2::TokenStream {
-            udf_impl(input, name, aliases, needs_context, can_return_errors, result_nullable)
+            let meta = UdfMeta {
+                name,
+                aliases,
+                needs_context,
+                can_return_errors,
+                result_nullable,
+                ..Default::default()
+            };
+            gen_udf_with_meta(input, meta)
         }
     }
 
+    fn gen_udf_with_meta(input: proc_macro2::TokenStream, meta: UdfMeta) -> proc_macro2::TokenStream {
+        udf_impl(input, meta).unwrap_or_else(syn::Error::into_compile_error)
+    }
+
+    // return the compile error message of the UDF
+    fn gen_udf_error(input: proc_macro2::TokenStream) -> String {
+        udf_impl(input, UdfMeta::default())
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn test_default_string_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_up(x: &str) -> String {
+                x.to_uppercase()
+            }
+        };
+
+        // the wrapper writes the output parameters through raw pointers, which is allowed for clippy
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_up_utf8(ctx: i64, x: *const libc::c_char, x_len: i32, out_len: *mut i32) -> *mut libc::c_char {
+                let x = match std::str::from_utf8(
+                    unsafe { std::slice::from_raw_parts(x as *const u8, x_len as usize) }
+                ) {
+                    Ok(value) => value,
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &format!("Invalid UTF-8 in parameter `x`: {}", err));
+                        unsafe {
+                            *out_len = 0;
+                        }
+                        return std::ptr::null_mut();
+                    }
+                };
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_up(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        unsafe {
+                            *out_len = 0;
+                        }
+                        return std::ptr::null_mut();
+                    }
+                };
+                gandiva_rust_udf_shared::return_gdv_string(ctx, &result, out_len)
+            }
+
+            pub fn register_my_up_utf8() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_up".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() },
+                    pc_name: "my_up_utf8".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf_with_meta(input, UdfMeta::default());
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
     #[test]
     fn test_no_arg_udf() {
         let input: proc_macro2::TokenStream = quote::quote! {
@@ -30,6 +111,56 @@ mod macro_tests {
             #input
 
             #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_(ctx: i64) -> f64 {
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf())) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return Default::default();
+                    }
+                };
+                result
+            }
+
+            pub fn register_my_udf_() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![],
+                    param_names: vec![],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "float64".to_string(), ..Default::default() },
+                    pc_name: "my_udf_".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+
+            // functions without arguments are warned unless their volatility is declared
+            const _: () = {
+                #[deprecated(note = "`my_udf` takes no arguments, declare whether its result may change with `#[udf(volatility = \"immutable\" | \"stable\" | \"volatile\")]` or `#[udf(deterministic = false)]`")]
+                struct VolatilityNotDeclared;
+                let _ = VolatilityNotDeclared;
+            };
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_volatile_no_arg_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf() -> f64 {
+                1.0
+            }
+        };
+
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
             pub extern "C" fn my_udf_() -> f64 {
                 let result = my_udf();
                 result
@@ -40,13 +171,20 @@ mod macro_tests {
                     name: "my_udf".to_string(),
                     aliases: vec![],
                     param_types: vec![],
+                    param_names: vec![],
                     return_type: gandiva_rust_udf_shared::DataType { type_name: "float64".to_string(), ..Default::default() },
                     pc_name: "my_udf_".to_string(),
+                    volatility: "volatile".to_string(),
                     ..Default::default()
                 });
             }
         };
-        let actual = gen_udf!(input);
+        let meta = UdfMeta {
+            panic: Some("abort".to_string()),
+            volatility: Some("volatile".to_string()),
+            ..Default::default()
+        };
+        let actual = gen_udf_with_meta(input, meta);
         assert_eq!(actual.to_string(), expected.to_string());
     }
 
@@ -62,8 +200,15 @@ mod macro_tests {
             #input
 
             #[no_mangle]
-            pub extern "C" fn my_udf_int64(x: i64) -> f64 {
-                let result = my_udf(x);
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64(ctx: i64, x: i64) -> f64 {
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return Default::default();
+                    }
+                };
                 result
             }
 
@@ -72,8 +217,11 @@ mod macro_tests {
                     name: "my_udf".to_string(),
                     aliases: vec![],
                     param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
                     return_type: gandiva_rust_udf_shared::DataType { type_name: "float64".to_string(), ..Default::default() },
                     pc_name: "my_udf_int64".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
                     ..Default::default()
                 });
             }
@@ -94,8 +242,15 @@ mod macro_tests {
             #input
 
             #[no_mangle]
-            pub extern "C" fn my_udf_boolean(x: bool) -> bool {
-                let result = my_udf(x);
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_boolean(ctx: i64, x: bool) -> bool {
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return false;
+                    }
+                };
                 result
             }
 
@@ -104,8 +259,11 @@ mod macro_tests {
                     name: "my_udf".to_string(),
                     aliases: vec![],
                     param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "boolean".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
                     return_type: gandiva_rust_udf_shared::DataType { type_name: "boolean".to_string(), ..Default::default() },
                     pc_name: "my_udf_boolean".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
                     ..Default::default()
                 });
             }
@@ -126,8 +284,15 @@ mod macro_tests {
             #input
 
             #[no_mangle]
-            pub extern "C" fn my_udf_int64_int32(x: i64, y: i32) -> f32 {
-                let result = my_udf(x, y);
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64_int32(ctx: i64, x: i64, y: i32) -> f32 {
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x, y))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return Default::default();
+                    }
+                };
                 result
             }
 
@@ -139,8 +304,11 @@ mod macro_tests {
                         gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
                         gandiva_rust_udf_shared::DataType { type_name: "int32".to_string(), ..Default::default() }
                     ],
+                    param_names: vec!["x".to_string(), "y".to_string()],
                     return_type: gandiva_rust_udf_shared::DataType { type_name: "float32".to_string(), ..Default::default() },
                     pc_name: "my_udf_int64_int32".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
                     ..Default::default()
                 });
             }
@@ -161,10 +329,24 @@ mod macro_tests {
             #input
 
             #[no_mangle]
-            pub extern "C" fn my_udf_utf8(x: *const libc::c_char, x_len: i32) -> bool {
-                let result = my_udf(std::str::from_utf8(
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_utf8(ctx: i64, x: *const libc::c_char, x_len: i32) -> bool {
+                let x = match std::str::from_utf8(
                     unsafe { std::slice::from_raw_parts(x as *const u8, x_len as usize) }
-                ).unwrap());
+                ) {
+                    Ok(value) => value,
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &format!("Invalid UTF-8 in parameter `x`: {}", err));
+                        return false;
+                    }
+                };
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return false;
+                    }
+                };
                 result
             }
 
@@ -173,8 +355,11 @@ mod macro_tests {
                     name: "my_udf".to_string(),
                     aliases: vec![],
                     param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
                     return_type: gandiva_rust_udf_shared::DataType { type_name: "boolean".to_string(), ..Default::default() },
                     pc_name: "my_udf_utf8".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
                     ..Default::default()
                 });
             }
@@ -195,10 +380,24 @@ mod macro_tests {
             #input
 
             #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
             pub extern "C" fn my_udf_utf8(ctx: i64, x: *const libc::c_char, x_len: i32) -> bool {
-                let result = my_udf(std::str::from_utf8(
+                let x = match std::str::from_utf8(
                     unsafe { std::slice::from_raw_parts(x as *const u8, x_len as usize) }
-                ).unwrap());
+                ) {
+                    Ok(value) => value,
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &format!("Invalid UTF-8 in parameter `x`: {}", err));
+                        return false;
+                    }
+                };
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return false;
+                    }
+                };
                 result
             }
 
@@ -207,9 +406,11 @@ mod macro_tests {
                     name: "my_udf".to_string(),
                     aliases: vec![],
                     param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
                     return_type: gandiva_rust_udf_shared::DataType { type_name: "boolean".to_string(), ..Default::default() },
                     pc_name: "my_udf_utf8".to_string(),
                     needs_context: true,
+                    can_return_errors: true,
                     ..Default::default()
                 });
             }
@@ -230,8 +431,18 @@ mod macro_tests {
             #input
 
             #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
             pub extern "C" fn my_udf_int64(ctx: i64, x: i64, out_len: *mut i32) -> *mut libc::c_char {
-                let result = my_udf(x);
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        unsafe {
+                            *out_len = 0;
+                        }
+                        return std::ptr::null_mut();
+                    }
+                };
                 gandiva_rust_udf_shared::return_gdv_string(ctx, &result, out_len)
             }
 
@@ -240,9 +451,11 @@ mod macro_tests {
                     name: "my_udf".to_string(),
                     aliases: vec![],
                     param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
                     return_type: gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() },
                     pc_name: "my_udf_int64".to_string(),
                     needs_context: true,
+                    can_return_errors: true,
                     ..Default::default()
                 });
             }
@@ -272,9 +485,64 @@ mod macro_tests {
             pub extern "C" fn finish_loading_registered_udfs(registry: *mut libc::c_char) {
                 gandiva_rust_udf_shared::free_udf_registry(registry);
             }
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn free_udf_holder_error(error_msg: *mut libc::c_char) {
+                unsafe {
+                    gandiva_rust_udf_shared::free_udf_holder_error(error_msg);
+                }
+            }
+        };
+        let actual = udf_registry_impl(quote::quote! {}, input).unwrap();
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_udf_registry_macro_with_defaults() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn register_all_udfs() {
+            }
+        };
+
+        // the defaults are applied after all UDFs are registered
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            pub extern "C" fn load_registered_udfs() -> *mut libc::c_char {
+                register_all_udfs();
+                gandiva_rust_udf_shared::apply_udf_registry_defaults(Some("net"), None);
+                let registry_c_str = gandiva_rust_udf_shared::get_udf_registry();
+                registry_c_str
+            }
+
+            #[no_mangle]
+            pub extern "C" fn finish_loading_registered_udfs(registry: *mut libc::c_char) {
+                gandiva_rust_udf_shared::free_udf_registry(registry);
+            }
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn free_udf_holder_error(error_msg: *mut libc::c_char) {
+                unsafe {
+                    gandiva_rust_udf_shared::free_udf_holder_error(error_msg);
+                }
+            }
         };
-        let actual = udf_registry_impl(input);
+        let attrs = quote::quote! { namespace = "net" };
+        let actual = udf_registry_impl(attrs, input).unwrap();
         assert_eq!(actual.to_string(), expected.to_string());
+
+        let attrs = quote::quote! { namespace = "net.ip" };
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn register_all_udfs() {
+            }
+        };
+        assert_eq!(
+            udf_registry_impl(attrs, input).unwrap_err().to_string(),
+            "The namespace must be a non-empty name without dots, e.g. \"net\"."
+        );
     }
 
     #[test]
@@ -289,8 +557,15 @@ mod macro_tests {
             #input
 
             #[no_mangle]
-            pub extern "C" fn my_udf_int64(x: i64) -> f64 {
-                let result = my_udf(x);
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64(ctx: i64, x: i64) -> f64 {
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return Default::default();
+                    }
+                };
                 result
             }
 
@@ -299,8 +574,11 @@ mod macro_tests {
                     name: "your_udf".to_string(),
                     aliases: vec![],
                     param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
                     return_type: gandiva_rust_udf_shared::DataType { type_name: "float64".to_string(), ..Default::default() },
                     pc_name: "my_udf_int64".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
                     ..Default::default()
                 });
             }
@@ -321,8 +599,15 @@ mod macro_tests {
             #input
 
             #[no_mangle]
-            pub extern "C" fn my_udf_int64(x: i64) -> f64 {
-                let result = my_udf(x);
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64(ctx: i64, x: i64) -> f64 {
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return Default::default();
+                    }
+                };
                 result
             }
 
@@ -331,8 +616,11 @@ mod macro_tests {
                     name: "my_udf".to_string(),
                     aliases: vec!["your_udf".to_string()],
                     param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
                     return_type: gandiva_rust_udf_shared::DataType { type_name: "float64".to_string(), ..Default::default() },
                     pc_name: "my_udf_int64".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
                     ..Default::default()
                 });
             }
@@ -353,8 +641,15 @@ mod macro_tests {
             #input
 
             #[no_mangle]
-            pub extern "C" fn my_udf_int64(x: i64) -> f64 {
-                let result = my_udf(x);
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64(ctx: i64, x: i64) -> f64 {
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return Default::default();
+                    }
+                };
                 result
             }
 
@@ -363,9 +658,12 @@ mod macro_tests {
                     name: "my_udf".to_string(),
                     aliases: vec![],
                     param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
                     return_type: gandiva_rust_udf_shared::DataType { type_name: "float64".to_string(), ..Default::default() },
                     pc_name: "my_udf_int64".to_string(),
                     result_nullable: "never".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
                     ..Default::default()
                 });
             }
@@ -382,13 +680,13 @@ mod macro_tests {
             needs_context = true,
             result_nullable = "never"
         };
-        let expected = (
-            Some("my_udf".to_string()),
-            vec!["your_udf".to_string()],
-            true,
-            false,
-            Some("never".to_string()),
-        );
+        let expected = UdfMeta {
+            name: Some("my_udf".to_string()),
+            aliases: vec!["your_udf".to_string()],
+            needs_context: true,
+            result_nullable: Some("never".to_string()),
+            ..Default::default()
+        };
         let actual = extract_udf_meta(input);
         assert_eq!(actual.unwrap(), expected);
     }
@@ -444,7 +742,10 @@ mod macro_tests {
         let input: proc_macro2::TokenStream = quote::quote! {
             result_nullable = "if_null"
         };
-        let expected = (None, vec![], false, false, Some("if_null".to_string()));
+        let expected = UdfMeta {
+            result_nullable: Some("if_null".to_string()),
+            ..Default::default()
+        };
         let actual = extract_udf_meta(input);
         assert_eq!(actual.unwrap(), expected);
     }
@@ -481,13 +782,13 @@ mod macro_tests {
             needs_context = false,
             result_nullable = "internal"
         };
-        let expected = (
-            Some("my_udf".to_string()),
-            vec!["your_udf".to_string(), "her_udf".to_string()],
-            false,
-            false,
-            Some("internal".to_string()),
-        );
+        let expected = UdfMeta {
+            name: Some("my_udf".to_string()),
+            aliases: vec!["your_udf".to_string(), "her_udf".to_string()],
+            needs_context: false,
+            result_nullable: Some("internal".to_string()),
+            ..Default::default()
+        };
         let actual = extract_udf_meta(input);
         assert_eq!(actual.unwrap(), expected);
     }
@@ -495,13 +796,7 @@ mod macro_tests {
     #[test]
     fn test_extract_udf_meta_default() {
         let input: proc_macro2::TokenStream = quote::quote! {};
-        let expected = (
-            None,
-            vec![],
-            false,
-            false,
-            None,
-        );
+        let expected = UdfMeta::default();
         let actual = extract_udf_meta(input);
         assert_eq!(actual.unwrap(), expected);
     }
@@ -511,13 +806,10 @@ mod macro_tests {
         let input: proc_macro2::TokenStream = quote::quote! {
             can_return_errors = true
         };
-        let expected = (
-            None,
-            vec![],
-            false,
-            true,
-            None,
-        );
+        let expected = UdfMeta {
+            can_return_errors: true,
+            ..Default::default()
+        };
         let actual = extract_udf_meta(input);
         assert_eq!(actual.unwrap(), expected);
     }
@@ -534,14 +826,28 @@ mod macro_tests {
             #input
 
             #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
             pub extern "C" fn my_udf_utf8(ctx: i64, x: *const libc::c_char, x_len: i32) -> bool {
-                let result = my_udf(std::str::from_utf8(
+                let x = match std::str::from_utf8(
                     unsafe { std::slice::from_raw_parts(x as *const u8, x_len as usize) }
-                ).unwrap());
+                ) {
+                    Ok(value) => value,
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &format!("Invalid UTF-8 in parameter `x`: {}", err));
+                        return false;
+                    }
+                };
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return false;
+                    }
+                };
                 match result {
                     Ok(return_value) => return_value,
                     Err(err) => {
-                        gandiva_rust_udf_shared::set_error_msg(ctx, &err);
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::error_message("", &err));
                         false
                     }
                 }
@@ -552,6 +858,7 @@ mod macro_tests {
                     name: "my_udf".to_string(),
                     aliases: vec![],
                     param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
                     return_type: gandiva_rust_udf_shared::DataType { type_name: "boolean".to_string(), ..Default::default() },
                     pc_name: "my_udf_utf8".to_string(),
                     needs_context: true,
@@ -572,20 +879,34 @@ mod macro_tests {
             }
         };
 
-        // int32 return value uses 0.into() as the default return value
+        // int32 return value uses its default value as the default return value
         let expected: proc_macro2::TokenStream = quote::quote! {
             #input
 
             #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
             pub extern "C" fn my_udf_utf8(ctx: i64, x: *const libc::c_char, x_len: i32) -> i32 {
-                let result = my_udf(std::str::from_utf8(
+                let x = match std::str::from_utf8(
                     unsafe { std::slice::from_raw_parts(x as *const u8, x_len as usize) }
-                ).unwrap());
+                ) {
+                    Ok(value) => value,
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &format!("Invalid UTF-8 in parameter `x`: {}", err));
+                        return Default::default();
+                    }
+                };
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return Default::default();
+                    }
+                };
                 match result {
                     Ok(return_value) => return_value,
                     Err(err) => {
-                        gandiva_rust_udf_shared::set_error_msg(ctx, &err);
-                        0.into()
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::error_message("", &err));
+                        Default::default()
                     }
                 }
             }
@@ -595,6 +916,7 @@ mod macro_tests {
                     name: "my_udf".to_string(),
                     aliases: vec![],
                     param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
                     return_type: gandiva_rust_udf_shared::DataType { type_name: "int32".to_string(), ..Default::default() },
                     pc_name: "my_udf_utf8".to_string(),
                     needs_context: true,
@@ -608,27 +930,144 @@ mod macro_tests {
     }
 
     #[test]
-    fn test_return_error_string_udf() {
+    fn test_return_error_prefix_udf() {
         let input: proc_macro2::TokenStream = quote::quote! {
-            pub fn my_udf(x: i64) -> Result<String, String> {
-                String::from("hello")
+            pub fn my_udf(x: i64) -> Result<i64, MyError> {
+                Ok(x)
             }
         };
 
+        // the error only needs to implement `Display`, and its message is prefixed with `error_prefix`
         let expected: proc_macro2::TokenStream = quote::quote! {
             #input
 
             #[no_mangle]
-            pub extern "C" fn my_udf_int64(ctx: i64, x: i64, out_len: *mut i32) -> *mut libc::c_char {
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64(ctx: i64, x: i64) -> i64 {
                 let result = my_udf(x);
                 match result {
-                    Ok(value) => gandiva_rust_udf_shared::return_gdv_string(ctx, &value, out_len),
+                    Ok(return_value) => return_value,
                     Err(err) => {
-                        gandiva_rust_udf_shared::set_error_msg(ctx, &err);
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::error_message("my_udf: ", &err));
+                        Default::default()
+                    }
+                }
+            }
+
+            pub fn register_my_udf_int64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                    pc_name: "my_udf_int64".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let meta = UdfMeta {
+            panic: Some("abort".to_string()),
+            error_prefix: Some("my_udf: ".to_string()),
+            ..Default::default()
+        };
+        let actual = gen_udf_with_meta(input, meta);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_extract_error_prefix() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            error_prefix = "my_udf: "
+        };
+        let expected = UdfMeta {
+            error_prefix: Some("my_udf: ".to_string()),
+            ..Default::default()
+        };
+        let actual = extract_udf_meta(input);
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    fn test_on_error_null_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: i64) -> Result<f32, String> {
+                Ok(x as f32)
+            }
+        };
+
+        // the error is returned as NULL without reporting it, so the function handles NULL internally
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64(x: i64, x_valid: bool, out_valid: *mut bool) -> f32 {
+                if !(x_valid) {
+                    unsafe {
+                        *out_valid = false;
+                    }
+                    return Default::default();
+                }
+                unsafe {
+                    *out_valid = true;
+                }
+                let result = my_udf(x);
+                match result {
+                    Ok(return_value) => return_value,
+                    Err(_) => {
                         unsafe {
-                            *out_len = 0;
+                            *out_valid = false;
                         }
-                        std::ptr::null_mut()
+                        Default::default()
+                    }
+                }
+            }
+
+            pub fn register_my_udf_int64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "float32".to_string(), ..Default::default() },
+                    pc_name: "my_udf_int64".to_string(),
+                    result_nullable: "internal".to_string(),
+                    ..Default::default()
+                });
+            }
+        };
+        let meta = UdfMeta {
+            panic: Some("abort".to_string()),
+            on_error: Some("null".to_string()),
+            ..Default::default()
+        };
+        let actual = gen_udf_with_meta(input, meta);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_on_error_default_string_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: i64) -> Result<String, String> {
+                Ok(x.to_string())
+            }
+        };
+
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64(ctx: i64, x: i64, out_len: *mut i32) -> *mut libc::c_char {
+                let result = my_udf(x);
+                match result {
+                    Ok(value) => gandiva_rust_udf_shared::return_gdv_string(ctx, &value, out_len),
+                    Err(_) => {
+                        let default_value: String = ("n/a").into();
+                        gandiva_rust_udf_shared::return_gdv_string(ctx, &default_value, out_len)
                     }
                 }
             }
@@ -638,15 +1077,2640 @@ mod macro_tests {
                     name: "my_udf".to_string(),
                     aliases: vec![],
                     param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
                     return_type: gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() },
                     pc_name: "my_udf_int64".to_string(),
                     needs_context: true,
-                    can_return_errors: true,
                     ..Default::default()
                 });
             }
         };
-        let actual = gen_udf!(input);
+        let meta = UdfMeta {
+            panic: Some("abort".to_string()),
+            on_error: Some("default".to_string()),
+            default: Some("\"n/a\"".to_string()),
+            ..Default::default()
+        };
+        let actual = gen_udf_with_meta(input, meta);
         assert_eq!(actual.to_string(), expected.to_string());
     }
+
+    #[test]
+    fn test_on_error_without_result_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: i64) -> i64 {
+                x
+            }
+        };
+        let meta = UdfMeta {
+            on_error: Some("null".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            udf_impl(input, meta).unwrap_err().to_string(),
+            "on_error = \"null\" only applies to UDFs returning `Result`."
+        );
+    }
+
+    #[test]
+    fn test_extract_on_error_default() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            on_error = "default", default = -1
+        };
+        let expected = UdfMeta {
+            on_error: Some("default".to_string()),
+            default: Some("- 1".to_string()),
+            ..Default::default()
+        };
+        let actual = extract_udf_meta(input);
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    fn test_extract_invalid_on_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            on_error = "ignore"
+        };
+        assert_eq!(
+            extract_udf_meta(input).unwrap_err().to_string(),
+            "Unsupported value for on_error attribute. Only fail, null, default are allowed."
+        );
+    }
+
+    #[test]
+    fn test_extract_default_without_on_error_default() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            on_error = "null", default = 0
+        };
+        assert_eq!(
+            extract_udf_meta(input).unwrap_err().to_string(),
+            "The default value is only used with `on_error = \"default\"`."
+        );
+    }
+
+    #[test]
+    fn test_context_param_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(ctx: &GdvContext, x: i64) -> i64 {
+                x
+            }
+        };
+
+        // the context is passed to the function instead of being a parameter of the gandiva function
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64(ctx: i64, x: i64) -> i64 {
+                let gdv_context = unsafe { gandiva_rust_udf_shared::GdvContext::new(ctx) };
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(&gdv_context, x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return Default::default();
+                    }
+                };
+                result
+            }
+
+            pub fn register_my_udf_int64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                    pc_name: "my_udf_int64".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_context_param_abort_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(ctx: &GdvContext, x: i64) -> i64 {
+                x
+            }
+        };
+
+        // the function may report errors through the context even if panics are not caught
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64(ctx: i64, x: i64) -> i64 {
+                let gdv_context = unsafe { gandiva_rust_udf_shared::GdvContext::new(ctx) };
+                let result = my_udf(&gdv_context, x);
+                result
+            }
+
+            pub fn register_my_udf_int64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                    pc_name: "my_udf_int64".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let meta = UdfMeta {
+            panic: Some("abort".to_string()),
+            ..Default::default()
+        };
+        let actual = gen_udf_with_meta(input, meta);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_context_param_not_first_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: i64, ctx: &GdvContext) -> i64 {
+                x
+            }
+        };
+        assert_eq!(
+            gen_udf_error(input),
+            "`GdvContext` must be the first parameter of the UDF."
+        );
+    }
+
+    #[test]
+    fn test_context_param_by_value_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(ctx: GdvContext<'static>, x: i64) -> i64 {
+                x
+            }
+        };
+        assert_eq!(
+            gen_udf_error(input),
+            "`GdvContext` must be borrowed, take `&GdvContext` instead."
+        );
+    }
+
+    #[test]
+    fn test_string_writer_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: i64, out: &mut GdvStringWriter) {
+                out.push_str("x").unwrap();
+            }
+        };
+
+        // the string is written into the arena memory and returned as is
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64(ctx: i64, x: i64, out_len: *mut i32) -> *mut libc::c_char {
+                let gdv_context = unsafe { gandiva_rust_udf_shared::GdvContext::new(ctx) };
+                let mut out = gandiva_rust_udf_shared::GdvStringWriter::new(&gdv_context);
+                match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x, &mut out))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        unsafe {
+                            *out_len = 0;
+                        }
+                        return std::ptr::null_mut();
+                    }
+                };
+                out.into_gdv_string(out_len)
+            }
+
+            pub fn register_my_udf_int64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() },
+                    pc_name: "my_udf_int64".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_return_error_string_writer_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: i64, out: &mut GdvStringWriter) -> Result<(), std::fmt::Error> {
+                write!(out, "{}", x)
+            }
+        };
+
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64(ctx: i64, x: i64, out_len: *mut i32) -> *mut libc::c_char {
+                let gdv_context = unsafe { gandiva_rust_udf_shared::GdvContext::new(ctx) };
+                let mut out = gandiva_rust_udf_shared::GdvStringWriter::new(&gdv_context);
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x, &mut out))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        unsafe {
+                            *out_len = 0;
+                        }
+                        return std::ptr::null_mut();
+                    }
+                };
+                match result {
+                    Ok(()) => out.into_gdv_string(out_len),
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::error_message("", &err));
+                        unsafe {
+                            *out_len = 0;
+                        }
+                        std::ptr::null_mut()
+                    }
+                }
+            }
+
+            pub fn register_my_udf_int64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() },
+                    pc_name: "my_udf_int64".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_string_writer_with_return_value_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: i64, out: &mut GdvStringWriter) -> i64 {
+                x
+            }
+        };
+        assert_eq!(
+            gen_udf_error(input),
+            "UDFs writing into `GdvStringWriter` must return `()`, `Option<()>` or `Result` of them."
+        );
+    }
+
+    #[test]
+    fn test_return_borrowed_str_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf<'a>(x: &'a str, y: i64) -> &'a str {
+                &x[..y as usize]
+            }
+        };
+
+        // the string parameters are captured before decoding, so that the result pointing into them is not copied
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_utf8_int64(ctx: i64, x: *const libc::c_char, x_len: i32, y: i64, out_len: *mut i32) -> *mut libc::c_char {
+                let borrowable_inputs = [(x as *const u8, x_len)];
+                let x = match std::str::from_utf8(
+                    unsafe { std::slice::from_raw_parts(x as *const u8, x_len as usize) }
+                ) {
+                    Ok(value) => value,
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &format!("Invalid UTF-8 in parameter `x`: {}", err));
+                        unsafe {
+                            *out_len = 0;
+                        }
+                        return std::ptr::null_mut();
+                    }
+                };
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x, y))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        unsafe {
+                            *out_len = 0;
+                        }
+                        return std::ptr::null_mut();
+                    }
+                };
+                gandiva_rust_udf_shared::return_gdv_borrowed_str(ctx, result, &borrowable_inputs, out_len)
+            }
+
+            pub fn register_my_udf_utf8_int64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![
+                        gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() },
+                        gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }
+                    ],
+                    param_names: vec!["x".to_string(), "y".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() },
+                    pc_name: "my_udf_utf8_int64".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_holder_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(holder: &MyHolder, x: i64, #[udf(literal)] y: i64) -> bool {
+                holder.contains(x)
+            }
+        };
+
+        // the holder is made by the library loader with the literal arguments and passed to the wrapper
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(holder: &MyHolder, x: i64, y: i64) -> bool {
+                holder.contains(x)
+            }
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64_int64(holder_ptr: i64, x: i64, y: i64) -> bool {
+                let result = my_udf(unsafe { gandiva_rust_udf_shared::udf_holder::<MyHolder>(holder_ptr) }, x, y);
+                result
+            }
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64_int64_make_holder(
+                literals: *const *const libc::c_char,
+                literal_lens: *const i32,
+                literal_count: i32,
+                error_msg: *mut *mut libc::c_char,
+            ) -> i64 {
+                unsafe {
+                    gandiva_rust_udf_shared::make_udf_holder::<MyHolder>(literals, literal_lens, literal_count, error_msg)
+                }
+            }
+
+            #[no_mangle]
+            pub extern "C" fn my_udf_int64_int64_free_holder(holder: i64) {
+                unsafe {
+                    gandiva_rust_udf_shared::free_udf_holder::<MyHolder>(holder)
+                }
+            }
+
+            pub fn register_my_udf_int64_int64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![
+                        gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                        gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }
+                    ],
+                    param_names: vec!["x".to_string(), "y".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "boolean".to_string(), ..Default::default() },
+                    pc_name: "my_udf_int64_int64".to_string(),
+                    holder_maker: Some("my_udf_int64_int64_make_holder".to_string()),
+                    holder_free: Some("my_udf_int64_int64_free_holder".to_string()),
+                    literal_params: vec![false, true],
+                    ..Default::default()
+                });
+            }
+        };
+        let meta = UdfMeta {
+            panic: Some("abort".to_string()),
+            holder: Some("MyHolder".to_string()),
+            ..Default::default()
+        };
+        let actual = gen_udf_with_meta(input, meta);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_holder_param_missing_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: i64) -> bool {
+                x > 0
+            }
+        };
+        let meta = UdfMeta {
+            holder: Some("MyHolder".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            udf_impl(input, meta).unwrap_err().to_string(),
+            "UDFs with a holder must take `&MyHolder` as the first parameter."
+        );
+    }
+
+    #[test]
+    fn test_holder_literal_missing_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(holder: &MyHolder, x: i64) -> bool {
+                holder.contains(x)
+            }
+        };
+        let meta = UdfMeta {
+            holder: Some("MyHolder".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            udf_impl(input, meta).unwrap_err().to_string(),
+            "UDFs with a holder must have literal parameters marked with `#[udf(literal)]`."
+        );
+    }
+
+    #[test]
+    fn test_literal_context_param_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(#[udf(literal)] ctx: &GdvContext, x: i64) -> bool {
+                x > 0
+            }
+        };
+        assert_eq!(
+            gen_udf_error(input),
+            "Only the parameters of the Gandiva function can be literals."
+        );
+    }
+
+    #[test]
+    fn test_literal_params_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: i64, #[literal] y: i64, z: i64) -> i64 {
+                x + y + z
+            }
+        };
+
+        // the literal parameters are marked by the parameter attribute or named by the function attribute
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: i64, y: i64, z: i64) -> i64 {
+                x + y + z
+            }
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64_int64_int64(x: i64, y: i64, z: i64) -> i64 {
+                let result = my_udf(x, y, z);
+                result
+            }
+
+            pub fn register_my_udf_int64_int64_int64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![
+                        gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                        gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                        gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }
+                    ],
+                    param_names: vec!["x".to_string(), "y".to_string(), "z".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                    pc_name: "my_udf_int64_int64_int64".to_string(),
+                    literal_params: vec![false, true, true],
+                    ..Default::default()
+                });
+            }
+        };
+        let meta = UdfMeta {
+            panic: Some("abort".to_string()),
+            literal: vec!["z".to_string()],
+            ..Default::default()
+        };
+        let actual = gen_udf_with_meta(input, meta);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_param_names_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(_unused: i64, r#type: i64) -> i64 {
+                r#type
+            }
+        };
+
+        // the registered names omit the raw identifier prefix and the leading underscores
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(_unused: i64, r#type: i64) -> i64 {
+                r#type
+            }
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64_int64(ctx: i64, _unused: i64, r#type: i64) -> i64 {
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(_unused, r#type))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return Default::default();
+                    }
+                };
+                result
+            }
+
+            pub fn register_my_udf_int64_int64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![
+                        gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                        gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }
+                    ],
+                    param_names: vec!["unused".to_string(), "type".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                    pc_name: "my_udf_int64_int64".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_unknown_literal_param_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: i64) -> i64 {
+                x
+            }
+        };
+        let meta = UdfMeta {
+            literal: vec!["pattern".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            udf_impl(input, meta).unwrap_err().to_string(),
+            "The literal parameter `pattern` is not a parameter of the UDF."
+        );
+    }
+
+    #[test]
+    fn test_extract_literal() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            literal = ["pattern", "format"]
+        };
+        let expected = UdfMeta {
+            literal: vec!["pattern".to_string(), "format".to_string()],
+            ..Default::default()
+        };
+        let actual = extract_udf_meta(input);
+        assert_eq!(actual.unwrap(), expected);
+
+        let input: proc_macro2::TokenStream = quote::quote! {
+            literal = ["pattern", "pattern"]
+        };
+        assert_eq!(
+            extract_udf_meta(input).unwrap_err().to_string(),
+            "Literal parameter `pattern` is specified more than once."
+        );
+    }
+
+    #[test]
+    fn test_doc_comment_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            /// Adds one to the value.
+            ///
+            /// Overflow wraps around.
+            pub fn my_udf(x: i64) -> i64 {
+                x.wrapping_add(1)
+            }
+        };
+
+        // the doc comments are registered as the description along with the examples
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            /// Adds one to the value.
+            ///
+            /// Overflow wraps around.
+            pub fn my_udf(x: i64) -> i64 {
+                x.wrapping_add(1)
+            }
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64(x: i64) -> i64 {
+                let result = my_udf(x);
+                result
+            }
+
+            pub fn register_my_udf_int64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                    pc_name: "my_udf_int64".to_string(),
+                    description: Some("Adds one to the value.\n\nOverflow wraps around.".to_string()),
+                    examples: vec!["my_udf(1) => 2".to_string()],
+                    ..Default::default()
+                });
+            }
+        };
+        let meta = UdfMeta {
+            panic: Some("abort".to_string()),
+            examples: vec!["my_udf(1) => 2".to_string()],
+            ..Default::default()
+        };
+        let actual = gen_udf_with_meta(input, meta);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_extract_description_examples() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            description = "Adds one to the value.", examples = ["my_udf(1) => 2", "my_udf(NULL) => NULL"]
+        };
+        let expected = UdfMeta {
+            description: Some("Adds one to the value.".to_string()),
+            examples: vec!["my_udf(1) => 2".to_string(), "my_udf(NULL) => NULL".to_string()],
+            ..Default::default()
+        };
+        let actual = extract_udf_meta(input);
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    fn test_extract_volatility() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            deterministic = false
+        };
+        let expected = UdfMeta {
+            volatility: Some("volatile".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(extract_udf_meta(input).unwrap(), expected);
+
+        let input: proc_macro2::TokenStream = quote::quote! {
+            volatility = "stable"
+        };
+        let expected = UdfMeta {
+            volatility: Some("stable".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(extract_udf_meta(input).unwrap(), expected);
+
+        let input: proc_macro2::TokenStream = quote::quote! {
+            volatility = "random"
+        };
+        assert_eq!(
+            extract_udf_meta(input).unwrap_err().to_string(),
+            "Unsupported value for volatility attribute. Only immutable, stable, volatile are allowed."
+        );
+
+        let input: proc_macro2::TokenStream = quote::quote! {
+            deterministic = true, volatility = "stable"
+        };
+        assert_eq!(
+            extract_udf_meta(input).unwrap_err().to_string(),
+            "Only one of deterministic and volatility can be specified."
+        );
+    }
+
+    #[test]
+    fn test_deprecated_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: i64) -> i64 {
+                x
+            }
+        };
+
+        // the deprecated aliases are registered as aliases too
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64(x: i64) -> i64 {
+                let result = my_udf(x);
+                result
+            }
+
+            pub fn register_my_udf_int64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec!["my_udf1".to_string(), "old_udf".to_string()],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                    pc_name: "my_udf_int64".to_string(),
+                    deprecated: Some("use your_udf instead".to_string()),
+                    deprecated_since: Some("0.3".to_string()),
+                    deprecated_aliases: vec!["old_udf".to_string()],
+                    ..Default::default()
+                });
+            }
+        };
+        let meta = UdfMeta {
+            aliases: vec!["my_udf1".to_string(), "old_udf".to_string()],
+            panic: Some("abort".to_string()),
+            deprecated: Some("use your_udf instead".to_string()),
+            since: Some("0.3".to_string()),
+            deprecated_aliases: vec!["old_udf".to_string()],
+            ..Default::default()
+        };
+        let actual = gen_udf_with_meta(input, meta);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_extract_deprecated() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            aliases = ["my_udf1", "old_udf" => deprecated], deprecated = "use your_udf instead", since = "0.3"
+        };
+        let expected = UdfMeta {
+            aliases: vec!["my_udf1".to_string(), "old_udf".to_string()],
+            deprecated: Some("use your_udf instead".to_string()),
+            since: Some("0.3".to_string()),
+            deprecated_aliases: vec!["old_udf".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(extract_udf_meta(input).unwrap(), expected);
+
+        let input: proc_macro2::TokenStream = quote::quote! {
+            aliases = ["old_udf" => hidden]
+        };
+        assert_eq!(
+            extract_udf_meta(input).unwrap_err().to_string(),
+            "Unsupported alias flag. Only deprecated is allowed."
+        );
+
+        let input: proc_macro2::TokenStream = quote::quote! {
+            since = "0.3"
+        };
+        assert_eq!(
+            extract_udf_meta(input).unwrap_err().to_string(),
+            "The since version is only used with `deprecated = \"...\"`."
+        );
+    }
+
+    #[test]
+    fn test_namespace_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn parse(x: i64) -> i64 {
+                x
+            }
+        };
+
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn parse_int64(x: i64) -> i64 {
+                let result = parse(x);
+                result
+            }
+
+            pub fn register_parse_int64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "parse".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                    pc_name: "parse_int64".to_string(),
+                    namespace: Some("net".to_string()),
+                    category: Some("network".to_string()),
+                    ..Default::default()
+                });
+            }
+        };
+        let meta = UdfMeta {
+            panic: Some("abort".to_string()),
+            namespace: Some("net".to_string()),
+            category: Some("network".to_string()),
+            ..Default::default()
+        };
+        let actual = gen_udf_with_meta(input, meta);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_extract_namespace_category() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            namespace = "net", category = "network"
+        };
+        let expected = UdfMeta {
+            namespace: Some("net".to_string()),
+            category: Some("network".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(extract_udf_meta(input).unwrap(), expected);
+
+        let input: proc_macro2::TokenStream = quote::quote! {
+            namespace = ""
+        };
+        assert_eq!(
+            extract_udf_meta(input).unwrap_err().to_string(),
+            "The namespace must be a non-empty name without dots, e.g. \"net\"."
+        );
+    }
+
+    #[test]
+    fn test_builtin_name_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn upper(x: i64) -> i64 {
+                x
+            }
+        };
+
+        // shadowing a built-in function is warned by default
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            const _: () = {
+                #[deprecated(note = "the UDF name `upper` shadows a Gandiva built-in function, rename it or allow it with `#[udf(shadow_builtin = \"allow\")]`")]
+                struct BuiltinFunctionShadowed;
+                let _ = BuiltinFunctionShadowed;
+            };
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn upper_int64(ctx: i64, x: i64) -> i64 {
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| upper(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return Default::default();
+                    }
+                };
+                result
+            }
+
+            pub fn register_upper_int64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "upper".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                    pc_name: "upper_int64".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: i64) -> i64 {
+                x
+            }
+        };
+        let meta = UdfMeta {
+            aliases: vec!["castVARCHAR".to_string(), "Upper".to_string()],
+            shadow_builtin: Some("error".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            udf_impl(input, meta).unwrap_err().to_string(),
+            "The UDF names `castVARCHAR`, `Upper` shadow Gandiva built-in functions."
+        );
+
+
+        // keywords and constants of the expression language are not functions of the Gandiva registry
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: i64) -> i64 {
+                x
+            }
+        };
+        let meta = UdfMeta {
+            aliases: vec!["e".to_string(), "in".to_string(), "and".to_string(), "or".to_string(), "not".to_string()],
+            shadow_builtin: Some("error".to_string()),
+            ..Default::default()
+        };
+        assert!(udf_impl(input, meta).is_ok());
+    }
+
+    #[test]
+    fn test_alias_same_as_function_name_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: i64) -> i64 {
+                x
+            }
+        };
+        let meta = UdfMeta {
+            aliases: vec!["my_udf".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            udf_impl(input, meta).unwrap_err().to_string(),
+            "The alias `my_udf` is the same as the function name."
+        );
+    }
+
+    #[test]
+    fn test_extract_invalid_names() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            name = "my udf"
+        };
+        assert_eq!(
+            extract_udf_meta(input).unwrap_err().to_string(),
+            "Invalid function name `my udf`, function names and aliases must be identifiers, e.g. \"my_func\"."
+        );
+
+        let input: proc_macro2::TokenStream = quote::quote! {
+            aliases = [""]
+        };
+        assert_eq!(
+            extract_udf_meta(input).unwrap_err().to_string(),
+            "Invalid function name ``, function names and aliases must be identifiers, e.g. \"my_func\"."
+        );
+
+        let input: proc_macro2::TokenStream = quote::quote! {
+            aliases = ["my_udf1", "my_udf1"]
+        };
+        assert_eq!(
+            extract_udf_meta(input).unwrap_err().to_string(),
+            "The alias `my_udf1` is specified more than once."
+        );
+
+        let input: proc_macro2::TokenStream = quote::quote! {
+            aliases = ["my_udf"], name = "my_udf"
+        };
+        assert_eq!(
+            extract_udf_meta(input).unwrap_err().to_string(),
+            "The alias `my_udf` is the same as the function name."
+        );
+
+        let input: proc_macro2::TokenStream = quote::quote! {
+            shadow_builtin = "ignore"
+        };
+        assert_eq!(
+            extract_udf_meta(input).unwrap_err().to_string(),
+            "Unsupported value for shadow_builtin attribute. Only warn, error, allow are allowed."
+        );
+    }
+
+    #[test]
+    fn test_crate_name_symbol_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn normalize(x: i64) -> i64 {
+                x
+            }
+        };
+
+        // the exported symbol is prefixed with the crate name, while the Rust names are kept
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[export_name = "my_crate__normalize_int64"]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn normalize_int64(x: i64) -> i64 {
+                let result = normalize(x);
+                result
+            }
+
+            pub fn register_normalize_int64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "normalize".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                    pc_name: "my_crate__normalize_int64".to_string(),
+                    ..Default::default()
+                });
+            }
+        };
+        let meta = UdfMeta {
+            panic: Some("abort".to_string()),
+            crate_name: Some("my_crate".to_string()),
+            ..Default::default()
+        };
+        let actual = gen_udf_with_meta(input, meta);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_pc_name_holder_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(holder: &MyHolder, #[udf(literal)] x: i64) -> i64 {
+                holder.get(x)
+            }
+        };
+
+        // the holder functions are exported with the symbol as their prefix
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(holder: &MyHolder, x: i64) -> i64 {
+                holder.get(x)
+            }
+
+            #[export_name = "my_symbol"]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64(holder_ptr: i64, x: i64) -> i64 {
+                let result = my_udf(unsafe { gandiva_rust_udf_shared::udf_holder::<MyHolder>(holder_ptr) }, x);
+                result
+            }
+
+            #[export_name = "my_symbol_make_holder"]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64_make_holder(
+                literals: *const *const libc::c_char,
+                literal_lens: *const i32,
+                literal_count: i32,
+                error_msg: *mut *mut libc::c_char,
+            ) -> i64 {
+                unsafe {
+                    gandiva_rust_udf_shared::make_udf_holder::<MyHolder>(literals, literal_lens, literal_count, error_msg)
+                }
+            }
+
+            #[export_name = "my_symbol_free_holder"]
+            pub extern "C" fn my_udf_int64_free_holder(holder: i64) {
+                unsafe {
+                    gandiva_rust_udf_shared::free_udf_holder::<MyHolder>(holder)
+                }
+            }
+
+            pub fn register_my_udf_int64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                    pc_name: "my_symbol".to_string(),
+                    holder_maker: Some("my_symbol_make_holder".to_string()),
+                    holder_free: Some("my_symbol_free_holder".to_string()),
+                    literal_params: vec![true],
+                    ..Default::default()
+                });
+            }
+        };
+        let meta = UdfMeta {
+            panic: Some("abort".to_string()),
+            holder: Some("MyHolder".to_string()),
+            pc_name: Some("my_symbol".to_string()),
+            crate_name: Some("my_crate".to_string()),
+            ..Default::default()
+        };
+        let actual = gen_udf_with_meta(input, meta);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_pc_name_generic_udf_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf<T>(x: T) -> T {
+                x
+            }
+        };
+        let meta = UdfMeta {
+            types: vec![("T".to_string(), vec!["i32".to_string(), "i64".to_string()])],
+            pc_name: Some("my_symbol".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            udf_impl(input, meta).unwrap_err().to_string(),
+            "pc_name cannot be specified for generic UDFs with more than one instantiation."
+        );
+    }
+
+    #[test]
+    fn test_extract_pc_name() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pc_name = "my_symbol"
+        };
+        let expected = UdfMeta {
+            pc_name: Some("my_symbol".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(extract_udf_meta(input).unwrap(), expected);
+
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pc_name = "my-symbol"
+        };
+        assert_eq!(
+            extract_udf_meta(input).unwrap_err().to_string(),
+            "Invalid pc_name `my-symbol`, the exported symbol must be an identifier."
+        );
+    }
+
+    #[test]
+    fn test_extract_holder() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            holder = my_mod::MyHolder
+        };
+        let expected = UdfMeta {
+            holder: Some("my_mod :: MyHolder".to_string()),
+            ..Default::default()
+        };
+        let actual = extract_udf_meta(input);
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    fn test_return_error_string_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: i64) -> Result<String, String> {
+                String::from("hello")
+            }
+        };
+
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64(ctx: i64, x: i64, out_len: *mut i32) -> *mut libc::c_char {
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        unsafe {
+                            *out_len = 0;
+                        }
+                        return std::ptr::null_mut();
+                    }
+                };
+                match result {
+                    Ok(value) => gandiva_rust_udf_shared::return_gdv_string(ctx, &value, out_len),
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::error_message("", &err));
+                        unsafe {
+                            *out_len = 0;
+                        }
+                        std::ptr::null_mut()
+                    }
+                }
+            }
+
+            pub fn register_my_udf_int64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() },
+                    pc_name: "my_udf_int64".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_option_arg_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: Option<i64>, y: i64) -> i64 {
+                x.unwrap_or(y)
+            }
+        };
+
+        // non `Option` parameters still make the result NULL if they are NULL
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64_int64(ctx: i64, x: i64, x_valid: bool, y: i64, y_valid: bool, out_valid: *mut bool) -> i64 {
+                if !(y_valid) {
+                    unsafe {
+                        *out_valid = false;
+                    }
+                    return Default::default();
+                }
+                unsafe {
+                    *out_valid = true;
+                }
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(if x_valid { Some(x) } else { None }, y))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return Default::default();
+                    }
+                };
+                result
+            }
+
+            pub fn register_my_udf_int64_int64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![
+                        gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                        gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }
+                    ],
+                    param_names: vec!["x".to_string(), "y".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                    pc_name: "my_udf_int64_int64".to_string(),
+                    result_nullable: "internal".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_option_str_arg_return_string_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: Option<&str>) -> String {
+                x.unwrap_or_default().to_string()
+            }
+        };
+
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_utf8(ctx: i64, x: *const libc::c_char, x_len: i32, x_valid: bool, out_valid: *mut bool, out_len: *mut i32) -> *mut libc::c_char {
+                unsafe {
+                    *out_valid = true;
+                }
+                let x = if x_valid { Some(match std::str::from_utf8(
+                    unsafe { std::slice::from_raw_parts(x as *const u8, x_len as usize) }
+                ) {
+                    Ok(value) => value,
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &format!("Invalid UTF-8 in parameter `x`: {}", err));
+                        unsafe {
+                            *out_len = 0;
+                        }
+                        return std::ptr::null_mut();
+                    }
+                }) } else { None };
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        unsafe {
+                            *out_len = 0;
+                        }
+                        return std::ptr::null_mut();
+                    }
+                };
+                gandiva_rust_udf_shared::return_gdv_string(ctx, &result, out_len)
+            }
+
+            pub fn register_my_udf_utf8() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() },
+                    pc_name: "my_udf_utf8".to_string(),
+                    result_nullable: "internal".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_return_option_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: i64) -> Option<i64> {
+                Some(x)
+            }
+        };
+
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64(ctx: i64, x: i64, x_valid: bool, out_valid: *mut bool) -> i64 {
+                if !(x_valid) {
+                    unsafe {
+                        *out_valid = false;
+                    }
+                    return Default::default();
+                }
+                unsafe {
+                    *out_valid = true;
+                }
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return Default::default();
+                    }
+                };
+                match result {
+                    Some(return_value) => return_value,
+                    None => {
+                        unsafe {
+                            *out_valid = false;
+                        }
+                        Default::default()
+                    }
+                }
+            }
+
+            pub fn register_my_udf_int64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                    pc_name: "my_udf_int64".to_string(),
+                    result_nullable: "internal".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_return_error_option_bool_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: Option<bool>) -> Result<Option<bool>, String> {
+                Ok(x)
+            }
+        };
+
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_boolean(ctx: i64, x: bool, x_valid: bool, out_valid: *mut bool) -> bool {
+                unsafe {
+                    *out_valid = true;
+                }
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(if x_valid { Some(x) } else { None }))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return false;
+                    }
+                };
+                match result {
+                    Ok(Some(return_value)) => return_value,
+                    Ok(None) => {
+                        unsafe {
+                            *out_valid = false;
+                        }
+                        Default::default()
+                    },
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::error_message("", &err));
+                        false
+                    }
+                }
+            }
+
+            pub fn register_my_udf_boolean() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "boolean".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "boolean".to_string(), ..Default::default() },
+                    pc_name: "my_udf_boolean".to_string(),
+                    result_nullable: "internal".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_return_option_string_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: &str) -> Option<String> {
+                Some(x.to_string())
+            }
+        };
+
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_utf8(ctx: i64, x: *const libc::c_char, x_len: i32, x_valid: bool, out_valid: *mut bool, out_len: *mut i32) -> *mut libc::c_char {
+                if !(x_valid) {
+                    unsafe {
+                        *out_valid = false;
+                        *out_len = 0;
+                    }
+                    return std::ptr::null_mut();
+                }
+                unsafe {
+                    *out_valid = true;
+                }
+                let x = match std::str::from_utf8(
+                    unsafe { std::slice::from_raw_parts(x as *const u8, x_len as usize) }
+                ) {
+                    Ok(value) => value,
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &format!("Invalid UTF-8 in parameter `x`: {}", err));
+                        unsafe {
+                            *out_len = 0;
+                        }
+                        return std::ptr::null_mut();
+                    }
+                };
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        unsafe {
+                            *out_len = 0;
+                        }
+                        return std::ptr::null_mut();
+                    }
+                };
+                match result {
+                    Some(value) => gandiva_rust_udf_shared::return_gdv_string(ctx, &value, out_len),
+                    None => {
+                        unsafe {
+                            *out_valid = false;
+                            *out_len = 0;
+                        }
+                        std::ptr::null_mut()
+                    }
+                }
+            }
+
+            pub fn register_my_udf_utf8() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() },
+                    pc_name: "my_udf_utf8".to_string(),
+                    result_nullable: "internal".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_binary_arg_return_binary_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: &[u8]) -> Vec<u8> {
+                x.to_vec()
+            }
+        };
+
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_binary(ctx: i64, x: *const libc::c_char, x_len: i32, out_len: *mut i32) -> *mut libc::c_char {
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(unsafe { std::slice::from_raw_parts(x as *const u8, x_len as usize) }))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        unsafe {
+                            *out_len = 0;
+                        }
+                        return std::ptr::null_mut();
+                    }
+                };
+                gandiva_rust_udf_shared::return_gdv_bytes(ctx, &result, out_len)
+            }
+
+            pub fn register_my_udf_binary() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "binary".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "binary".to_string(), ..Default::default() },
+                    pc_name: "my_udf_binary".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_return_fixed_size_binary_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: &str) -> [u8; 16] {
+                [0; 16]
+            }
+        };
+
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_utf8(ctx: i64, x: *const libc::c_char, x_len: i32, out_len: *mut i32) -> *mut libc::c_char {
+                let x = match std::str::from_utf8(
+                    unsafe { std::slice::from_raw_parts(x as *const u8, x_len as usize) }
+                ) {
+                    Ok(value) => value,
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &format!("Invalid UTF-8 in parameter `x`: {}", err));
+                        unsafe {
+                            *out_len = 0;
+                        }
+                        return std::ptr::null_mut();
+                    }
+                };
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        unsafe {
+                            *out_len = 0;
+                        }
+                        return std::ptr::null_mut();
+                    }
+                };
+                gandiva_rust_udf_shared::return_gdv_bytes(ctx, &result, out_len)
+            }
+
+            pub fn register_my_udf_utf8() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType {
+                        type_name: "fixed_size_binary".to_string(),
+                        byte_width: Some(16i32),
+                        ..Default::default()
+                    },
+                    pc_name: "my_udf_utf8".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_decimal_arg_return_decimal_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: Decimal128<10, 2>) -> Decimal128<12, 2> {
+                Decimal128::new(x.value())
+            }
+        };
+
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_decimal(
+                ctx: i64,
+                x_high: i64, x_low: u64, x_precision: i32, x_scale: i32,
+                out_precision: i32, out_scale: i32, out_high: *mut i64, out_low: *mut u64
+            ) {
+                let x = match gandiva_rust_udf_shared::Decimal128::from_gdv_parts(x_high, x_low, x_precision, x_scale) {
+                    Ok(value) => value,
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &format!("Invalid decimal in parameter `x`: {}", err));
+                        return;
+                    }
+                };
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return;
+                    }
+                };
+                let return_value: Decimal128<12, 2> = result;
+                let (high, low) = match return_value.to_gdv_parts(out_precision, out_scale) {
+                    Ok(parts) => parts,
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &format!("Invalid decimal result: {}", err));
+                        (0, 0)
+                    }
+                };
+                unsafe {
+                    *out_high = high;
+                    *out_low = low;
+                }
+            }
+
+            pub fn register_my_udf_decimal() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType {
+                        type_name: "decimal".to_string(),
+                        precision: Some(10i32),
+                        scale: Some(2i32),
+                        ..Default::default()
+                    }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType {
+                        type_name: "decimal".to_string(),
+                        precision: Some(12i32),
+                        scale: Some(2i32),
+                        ..Default::default()
+                    },
+                    pc_name: "my_udf_decimal".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_return_error_decimal_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: &str) -> Result<Decimal128<38, 4>, String> {
+                Ok(Decimal128::new(0))
+            }
+        };
+
+        // decimal return value uses Default::default() as the default return value
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_utf8(ctx: i64, x: *const libc::c_char, x_len: i32, out_precision: i32, out_scale: i32, out_high: *mut i64, out_low: *mut u64) {
+                let x = match std::str::from_utf8(
+                    unsafe { std::slice::from_raw_parts(x as *const u8, x_len as usize) }
+                ) {
+                    Ok(value) => value,
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &format!("Invalid UTF-8 in parameter `x`: {}", err));
+                        return;
+                    }
+                };
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return;
+                    }
+                };
+                let return_value: Decimal128<38, 4> = match result {
+                    Ok(return_value) => return_value,
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::error_message("", &err));
+                        Default::default()
+                    }
+                };
+                let (high, low) = match return_value.to_gdv_parts(out_precision, out_scale) {
+                    Ok(parts) => parts,
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &format!("Invalid decimal result: {}", err));
+                        (0, 0)
+                    }
+                };
+                unsafe {
+                    *out_high = high;
+                    *out_low = low;
+                }
+            }
+
+            pub fn register_my_udf_utf8() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType {
+                        type_name: "decimal".to_string(),
+                        precision: Some(38i32),
+                        scale: Some(4i32),
+                        ..Default::default()
+                    },
+                    pc_name: "my_udf_utf8".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_decimal_wrapper_signature() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: Option<Decimal128<10, 2> >, y: &str) -> Option<Decimal128<12, 2> > {
+                x.map(|x| Decimal128::new(x.value()))
+            }
+        };
+
+        // the decimal result parameters follow `out_valid`, with the result precision and scale
+        // before its high and low 64 bits
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            extern "C" fn my_udf_decimal_utf8(
+                ctx: i64,
+                x_high: i64, x_low: u64, x_precision: i32, x_scale: i32, x_valid: bool,
+                y: *const libc::c_char, y_len: i32, y_valid: bool,
+                out_valid: *mut bool,
+                out_precision: i32, out_scale: i32, out_high: *mut i64, out_low: *mut u64
+            )
+        };
+        let actual: syn::File = syn::parse2(gen_udf!(input)).unwrap();
+        let wrapper = actual
+            .items
+            .iter()
+            .find_map(|item| match item {
+                syn::Item::Fn(function) if function.sig.ident == "my_udf_decimal_utf8" => Some(&function.sig),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(quote::quote!(#wrapper).to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_timestamp_arg_return_date_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: Option<Timestamp<Millisecond> >) -> Date32 {
+                Date32(0)
+            }
+        };
+
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_timestamp(ctx: i64, x: Timestamp<Millisecond>, x_valid: bool, out_valid: *mut bool) -> Date32 {
+                unsafe {
+                    *out_valid = true;
+                }
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(if x_valid { Some(x) } else { None }))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return Default::default();
+                    }
+                };
+                result
+            }
+
+            pub fn register_my_udf_timestamp() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType {
+                        type_name: "timestamp".to_string(),
+                        unit: Some("MILLI".to_string()),
+                        ..Default::default()
+                    }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "date32".to_string(), ..Default::default() },
+                    pc_name: "my_udf_timestamp".to_string(),
+                    result_nullable: "internal".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_return_error_time_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: Date64) -> Result<Time64<Nanosecond>, String> {
+                Ok(Time64::new(0))
+            }
+        };
+
+        // time return value uses Default::default() as the default return value
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_date64(ctx: i64, x: Date64) -> Time64<Nanosecond> {
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return Default::default();
+                    }
+                };
+                match result {
+                    Ok(return_value) => return_value,
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::error_message("", &err));
+                        Default::default()
+                    }
+                }
+            }
+
+            pub fn register_my_udf_date64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "date64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType {
+                        type_name: "time64".to_string(),
+                        unit: Some("NANO".to_string()),
+                        ..Default::default()
+                    },
+                    pc_name: "my_udf_date64".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_list_args_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: &[i64], y: StrList) -> i64 {
+                x.len() as i64
+            }
+        };
+
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_list_int64_list_utf8(
+                ctx: i64,
+                x: *const i64, x_len: i32,
+                y: *const libc::c_char, y_offsets: *const i32, y_len: i32
+            ) -> i64 {
+                let y = match unsafe {
+                    gandiva_rust_udf_shared::StrList::from_gdv_parts_checked(y, y_offsets, y_len)
+                } {
+                    Ok(value) => value,
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &format!("Invalid UTF-8 in parameter `y`: {}", err));
+                        return Default::default();
+                    }
+                };
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(unsafe { std::slice::from_raw_parts(x, x_len as usize) }, y))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return Default::default();
+                    }
+                };
+                result
+            }
+
+            pub fn register_my_udf_list_int64_list_utf8() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![
+                        gandiva_rust_udf_shared::DataType {
+                            type_name: "list".to_string(),
+                            value_type: Some(Box::new(gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() })),
+                            ..Default::default()
+                        },
+                        gandiva_rust_udf_shared::DataType {
+                            type_name: "list".to_string(),
+                            value_type: Some(Box::new(gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() })),
+                            ..Default::default()
+                        }
+                    ],
+                    param_names: vec!["x".to_string(), "y".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                    pc_name: "my_udf_list_int64_list_utf8".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_unchecked_str_list_arg_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(#[udf(utf8 = "unchecked")] x: StrList) -> i64 {
+                x.len() as i64
+            }
+        };
+
+        // the strings of an unchecked list are not validated
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: StrList) -> i64 {
+                x.len() as i64
+            }
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_list_utf8(ctx: i64, x: *const libc::c_char, x_offsets: *const i32, x_len: i32) -> i64 {
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(unsafe { gandiva_rust_udf_shared::StrList::from_gdv_parts(x, x_offsets, x_len) }))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return Default::default();
+                    }
+                };
+                result
+            }
+
+            pub fn register_my_udf_list_utf8() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType {
+                        type_name: "list".to_string(),
+                        value_type: Some(Box::new(gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() })),
+                        ..Default::default()
+                    }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                    pc_name: "my_udf_list_utf8".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf!(input);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_lossy_str_list_arg_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(#[udf(utf8 = "lossy")] x: StrList) -> i64 {
+                x.len() as i64
+            }
+        };
+        assert_eq!(
+            gen_udf_error(input),
+            "UTF-8 lossy is not supported for `StrList`, \
+            use `#[udf(utf8 = \"error\")]` or `#[udf(utf8 = \"unchecked\")]` for the parameter instead."
+        );
+    }
+
+    #[test]
+    fn test_no_return_type_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: i64) {}
+        };
+        assert_eq!(
+            gen_udf_error(input),
+            "UDFs must return a value, add a return type such as `-> i64`."
+        );
+    }
+
+    #[test]
+    fn test_self_receiver_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(&self, x: i64) -> i64 {
+                x
+            }
+        };
+        assert_eq!(
+            gen_udf_error(input),
+            "UDFs cannot take a `self` receiver, define the UDF as a free function instead."
+        );
+    }
+
+    #[test]
+    fn test_malformed_result_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: i64) -> Result<i64> {
+                Ok(x)
+            }
+        };
+        assert_eq!(
+            gen_udf_error(input),
+            "Result type is not well-formed and it is expected to have two generic parameters, e.g. `Result<i64, String>`."
+        );
+    }
+
+    #[test]
+    fn test_non_ident_parameter_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf((x, y): (i64, i64)) -> i64 {
+                x + y
+            }
+        };
+        assert_eq!(
+            gen_udf_error(input),
+            "UDF parameters must be plain identifiers, e.g. `x: i64`."
+        );
+    }
+
+    #[test]
+    fn test_generic_udf_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf<T>(x: T) -> T {
+                x
+            }
+        };
+        assert_eq!(
+            gen_udf_error(input),
+            "Generic UDFs must specify the types to instantiate `T` with, e.g. `#[udf(types(T = [i32, i64]))]`."
+        );
+    }
+
+    #[test]
+    fn test_const_generic_udf_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf<const N: i64>(x: i64) -> i64 {
+                x + N
+            }
+        };
+        assert_eq!(
+            gen_udf_error(input),
+            "Const generic UDFs are not supported, use concrete parameter types instead."
+        );
+    }
+
+    #[test]
+    fn test_option_arg_conflicting_result_nullable_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: Option<i64>) -> i64 {
+                x.unwrap_or(0)
+            }
+        };
+        let meta = UdfMeta {
+            result_nullable: Some("never".to_string()),
+            ..Default::default()
+        };
+        let actual = udf_impl(input, meta);
+        assert_eq!(
+            actual.unwrap_err().to_string(),
+            "Functions with `Option` parameters or `Option` result handle NULL internally, \
+            result_nullable must be internal instead of never."
+        );
+    }
+
+    #[test]
+    fn test_not_a_function_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub struct MyUdf;
+        };
+        assert_eq!(gen_udf_error(input), "expected `fn`");
+    }
+
+    #[test]
+    fn test_unsupported_param_type_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: Vec<i32>) -> i64 {
+                x.len() as i64
+            }
+        };
+        assert_eq!(
+            gen_udf_error(input),
+            "Unsupported parameter type `Vec < i32 >`, supported types are bool, i8, i16, i32, i64, \
+            u8, u16, u32, u64, f32, f64, &str, Cow<str>, &[u8], Decimal128<P, S>, Date32, Date64, Time32<U>, \
+            Time64<U>, Timestamp<U>, &[T] of numeric types, StrList, and `Option` of them. \
+            Custom types implementing `gandiva_rust_udf_shared::GdvType` are allowed with \
+            `#[udf(unchecked_types = true)]`."
+        );
+    }
+
+    #[test]
+    fn test_unsupported_return_type_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: &[u8]) -> Result<&[u8], String> {
+                Ok(x)
+            }
+        };
+        assert_eq!(
+            gen_udf_error(input),
+            "Unsupported return type `& [u8]`, supported types are bool, i8, i16, i32, i64, \
+            u8, u16, u32, u64, f32, f64, String, &str, Vec<u8>, Box<[u8]>, [u8; N], Decimal128<P, S>, \
+            Date32, Date64, Time32<U>, Time64<U>, Timestamp<U>, and `Option`/`Result` of them."
+        );
+    }
+
+    #[test]
+    fn test_unchecked_custom_type_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: UserId) -> UserId {
+                x
+            }
+        };
+
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_userid(x: UserId) -> UserId {
+                let result = my_udf(x);
+                result
+            }
+
+            pub fn register_my_udf_userid() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType {
+                        type_name: <UserId as gandiva_rust_udf_shared::GdvType>::TYPE_NAME.to_string(),
+                        ..Default::default()
+                    }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType {
+                        type_name: <UserId as gandiva_rust_udf_shared::GdvType>::TYPE_NAME.to_string(),
+                        ..Default::default()
+                    },
+                    pc_name: "my_udf_userid".to_string(),
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf_with_meta(
+            input,
+            UdfMeta {
+                unchecked_types: true,
+                panic: Some("abort".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_extract_unchecked_types() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            unchecked_types = true
+        };
+        let expected = UdfMeta {
+            unchecked_types: true,
+            ..Default::default()
+        };
+        let actual = extract_udf_meta(input);
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    fn test_catch_panic_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: i64) -> i64 {
+                x
+            }
+        };
+
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_int64(ctx: i64, x: i64) -> i64 {
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        return Default::default();
+                    }
+                };
+                result
+            }
+
+            pub fn register_my_udf_int64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                    pc_name: "my_udf_int64".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf_with_meta(
+            input.clone(),
+            UdfMeta {
+                panic: Some("error".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(actual.to_string(), expected.to_string());
+        // panics are caught by default
+        let actual = gen_udf_with_meta(input, UdfMeta::default());
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_catch_panic_string_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: &str) -> String {
+                x.to_string()
+            }
+        };
+
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_utf8(ctx: i64, x: *const libc::c_char, x_len: i32, out_len: *mut i32) -> *mut libc::c_char {
+                let x = match std::str::from_utf8(
+                    unsafe { std::slice::from_raw_parts(x as *const u8, x_len as usize) }
+                ) {
+                    Ok(value) => value,
+                    Err(err) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &format!("Invalid UTF-8 in parameter `x`: {}", err));
+                        unsafe {
+                            *out_len = 0;
+                        }
+                        return std::ptr::null_mut();
+                    }
+                };
+                let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| my_udf(x))) {
+                    Ok(result) => result,
+                    Err(panic) => {
+                        gandiva_rust_udf_shared::set_error_msg(ctx, &gandiva_rust_udf_shared::panic_message(&*panic));
+                        unsafe {
+                            *out_len = 0;
+                        }
+                        return std::ptr::null_mut();
+                    }
+                };
+                gandiva_rust_udf_shared::return_gdv_string(ctx, &result, out_len)
+            }
+
+            pub fn register_my_udf_utf8() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() },
+                    pc_name: "my_udf_utf8".to_string(),
+                    needs_context: true,
+                    can_return_errors: true,
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf_with_meta(input, UdfMeta::default());
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_extract_panic() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            panic = "abort"
+        };
+        let expected = UdfMeta {
+            panic: Some("abort".to_string()),
+            ..Default::default()
+        };
+        let actual = extract_udf_meta(input);
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    fn test_extract_invalid_panic_value() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            panic = "ignore"
+        };
+        let actual = extract_udf_meta(input);
+        assert_eq!(
+            actual.err().unwrap().to_string(),
+            "Unsupported value for panic attribute. Only error, abort are allowed."
+        );
+    }
+
+    #[test]
+    fn test_utf8_lossy_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: Cow<str>) -> i64 {
+                x.len() as i64
+            }
+        };
+
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_utf8(x: *const libc::c_char, x_len: i32) -> i64 {
+                let result = my_udf(String::from_utf8_lossy(
+                    unsafe { std::slice::from_raw_parts(x as *const u8, x_len as usize) }
+                ));
+                result
+            }
+
+            pub fn register_my_udf_utf8() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() }],
+                    param_names: vec!["x".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                    pc_name: "my_udf_utf8".to_string(),
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf_with_meta(
+            input,
+            UdfMeta {
+                panic: Some("abort".to_string()),
+                utf8: Some("lossy".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_utf8_param_policy_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(#[udf(utf8 = "unchecked")] x: &str, y: Cow<str>) -> i64 {
+                (x.len() + y.len()) as i64
+            }
+        };
+
+        // the parameter attribute is removed from the function
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: &str, y: Cow<str>) -> i64 {
+                (x.len() + y.len()) as i64
+            }
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn my_udf_utf8_utf8(
+                x: *const libc::c_char, x_len: i32, y: *const libc::c_char, y_len: i32
+            ) -> i64 {
+                let result = my_udf(
+                    unsafe { std::str::from_utf8_unchecked(
+                        std::slice::from_raw_parts(x as *const u8, x_len as usize)
+                    ) },
+                    String::from_utf8_lossy(
+                        unsafe { std::slice::from_raw_parts(y as *const u8, y_len as usize) }
+                    )
+                );
+                result
+            }
+
+            pub fn register_my_udf_utf8_utf8() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "my_udf".to_string(),
+                    aliases: vec![],
+                    param_types: vec![
+                        gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() },
+                        gandiva_rust_udf_shared::DataType { type_name: "utf8".to_string(), ..Default::default() }
+                    ],
+                    param_names: vec!["x".to_string(), "y".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "int64".to_string(), ..Default::default() },
+                    pc_name: "my_udf_utf8_utf8".to_string(),
+                    ..Default::default()
+                });
+            }
+        };
+        let actual = gen_udf_with_meta(
+            input,
+            UdfMeta {
+                panic: Some("abort".to_string()),
+                utf8: Some("lossy".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_utf8_lossy_str_param_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(#[udf(utf8 = "lossy")] x: &str) -> i64 {
+                x.len() as i64
+            }
+        };
+        assert_eq!(
+            gen_udf_error(input),
+            "UTF-8 lossy parameters receive a `Cow<str>`, declare the parameter as `Cow<str>` instead."
+        );
+    }
+
+    #[test]
+    fn test_unknown_param_attribute_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(#[udf(name = "x")] x: &str) -> i64 {
+                x.len() as i64
+            }
+        };
+        assert_eq!(gen_udf_error(input), "Unknown attribute for UDF parameter");
+    }
+
+    #[test]
+    fn test_strip_param_attrs_on_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(#[udf(name = "x")] x: &str, #[literal] #[udf(utf8 = "lossy")] y: &str) -> i64 {
+                x.len() as i64
+            }
+        };
+
+        // the function re-emitted with the error keeps no parameter attributes
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf(x: &str, y: &str) -> i64 {
+                x.len() as i64
+            }
+        };
+        assert_eq!(strip_param_attrs(input).to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_extract_utf8() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            utf8 = "unchecked"
+        };
+        let expected = UdfMeta {
+            utf8: Some("unchecked".to_string()),
+            ..Default::default()
+        };
+        let actual = extract_udf_meta(input);
+        assert_eq!(actual.unwrap(), expected);
+    }
+
+    #[test]
+    fn test_extract_invalid_utf8_value() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            utf8 = "ignore"
+        };
+        let actual = extract_udf_meta(input);
+        assert_eq!(
+            actual.err().unwrap().to_string(),
+            "Unsupported value for utf8 attribute. Only error, lossy, unchecked are allowed."
+        );
+    }
+
+    #[test]
+    fn test_generic_udf() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn abs_diff<T: PartialOrd + std::ops::Sub<Output = T> >(a: T, b: T) -> T {
+                if a > b { a - b } else { b - a }
+            }
+        };
+
+        let expected: proc_macro2::TokenStream = quote::quote! {
+            #input
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn abs_diff_int32_int32(a: i32, b: i32) -> i32 {
+                let result = abs_diff::<i32>(a, b);
+                result
+            }
+
+            pub fn register_abs_diff_int32_int32() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "abs_diff".to_string(),
+                    aliases: vec![],
+                    param_types: vec![
+                        gandiva_rust_udf_shared::DataType { type_name: "int32".to_string(), ..Default::default() },
+                        gandiva_rust_udf_shared::DataType { type_name: "int32".to_string(), ..Default::default() }
+                    ],
+                    param_names: vec!["a".to_string(), "b".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "int32".to_string(), ..Default::default() },
+                    pc_name: "abs_diff_int32_int32".to_string(),
+                    ..Default::default()
+                });
+            }
+
+            #[no_mangle]
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            pub extern "C" fn abs_diff_float64_float64(a: f64, b: f64) -> f64 {
+                let result = abs_diff::<f64>(a, b);
+                result
+            }
+
+            pub fn register_abs_diff_float64_float64() {
+                gandiva_rust_udf_shared::register_udf(gandiva_rust_udf_shared::UdfMetaData {
+                    name: "abs_diff".to_string(),
+                    aliases: vec![],
+                    param_types: vec![
+                        gandiva_rust_udf_shared::DataType { type_name: "float64".to_string(), ..Default::default() },
+                        gandiva_rust_udf_shared::DataType { type_name: "float64".to_string(), ..Default::default() }
+                    ],
+                    param_names: vec!["a".to_string(), "b".to_string()],
+                    return_type: gandiva_rust_udf_shared::DataType { type_name: "float64".to_string(), ..Default::default() },
+                    pc_name: "abs_diff_float64_float64".to_string(),
+                    ..Default::default()
+                });
+            }
+        };
+        let meta = UdfMeta {
+            panic: Some("abort".to_string()),
+            types: vec![("T".to_string(), vec!["i32".to_string(), "f64".to_string()])],
+            ..Default::default()
+        };
+        let actual = gen_udf_with_meta(input, meta);
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_generic_udf_same_parameter_types_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf<T: Default>(x: i64) -> T {
+                T::default()
+            }
+        };
+        let meta = UdfMeta {
+            types: vec![("T".to_string(), vec!["i32".to_string(), "i64".to_string()])],
+            ..Default::default()
+        };
+        assert_eq!(
+            udf_impl(input, meta).unwrap_err().to_string(),
+            "Instantiations of generic UDFs must have different parameter types, \
+            `my_udf_int64` is generated more than once."
+        );
+    }
+
+    #[test]
+    fn test_generic_udf_unknown_type_parameter_error() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            pub fn my_udf<T>(x: T) -> T {
+                x
+            }
+        };
+        let meta = UdfMeta {
+            types: vec![("U".to_string(), vec!["i32".to_string()])],
+            ..Default::default()
+        };
+        assert_eq!(
+            udf_impl(input, meta).unwrap_err().to_string(),
+            "`U` in `types` is not a generic type parameter of the UDF."
+        );
+    }
+
+    #[test]
+    fn test_extract_types() {
+        let input: proc_macro2::TokenStream = quote::quote! {
+            types(T = [i32, Option<i64>], U = [f64])
+        };
+        let expected = UdfMeta {
+            types: vec![
+                ("T".to_string(), vec!["i32".to_string(), "Option < i64 >".to_string()]),
+                ("U".to_string(), vec!["f64".to_string()]),
+            ],
+            ..Default::default()
+        };
+        let actual = extract_udf_meta(input);
+        assert_eq!(actual.unwrap(), expected);
+    }
 }
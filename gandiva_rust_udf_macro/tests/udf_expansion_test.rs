@@ -0,0 +1,103 @@
+// the UDFs are expanded by the `udf` macro in a downstream crate, so that the generated wrappers
+// are compiled and linted by clippy like in the crates defining UDFs
+use gandiva_rust_udf_macro::{udf, udf_registry};
+use gandiva_rust_udf_shared::{Decimal128, StrList, UdfHolder};
+
+#[udf]
+pub fn my_up(x: &str) -> String {
+    x.to_uppercase()
+}
+
+#[udf(on_error = "null")]
+pub fn my_parse(x: Option<&str>) -> Result<i64, std::num::ParseIntError> {
+    x.unwrap_or("0").parse()
+}
+
+#[udf]
+pub fn my_scale(x: Decimal128<10, 2>) -> Decimal128<12, 3> {
+    Decimal128::new(x.value())
+}
+
+#[udf]
+pub fn my_join(x: StrList) -> String {
+    x.iter().collect::<Vec<_>>().join(",")
+}
+
+pub struct PrefixHolder {
+    prefix: String,
+}
+
+impl UdfHolder for PrefixHolder {
+    type Error = String;
+
+    fn make(literals: &[&str]) -> Result<Self, Self::Error> {
+        match literals {
+            [prefix] if !prefix.is_empty() => Ok(PrefixHolder { prefix: prefix.to_string() }),
+            _ => Err("prefix must not be empty".to_string()),
+        }
+    }
+}
+
+#[udf(holder = PrefixHolder)]
+pub fn my_has_prefix(holder: &PrefixHolder, x: &str, #[udf(literal)] _prefix: &str) -> bool {
+    x.starts_with(&holder.prefix)
+}
+
+#[udf_registry]
+pub fn register_udfs() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gandiva_rust_udf_shared::initialize_gdv_fn_context;
+
+    extern "C" fn gdv_fn_context_arena_malloc(_context: i64, size: i32) -> *mut i8 {
+        let buffer = vec![0u8; size as usize + 1].into_boxed_slice();
+        Box::into_raw(buffer).cast()
+    }
+
+    extern "C" fn gdv_fn_context_set_error_msg(_context: i64, _error_msg: *const i8) {}
+
+    #[test]
+    fn test_expanded_wrappers() {
+        initialize_gdv_fn_context(gdv_fn_context_arena_malloc, gdv_fn_context_set_error_msg);
+        let x = "abc";
+        let mut out_len = 0;
+        let result = my_up_utf8(0, x.as_ptr().cast(), x.len() as i32, &mut out_len);
+        let result = unsafe { std::slice::from_raw_parts(result as *const u8, out_len as usize) };
+        assert_eq!(result, b"ABC");
+
+        let mut out_valid = false;
+        assert_eq!(my_parse_utf8(0, x.as_ptr().cast(), x.len() as i32, true, &mut out_valid), 0);
+        assert!(!out_valid);
+
+        let (mut high, mut low) = (0, 0);
+        my_scale_decimal(0, 0, 12345, 10, 2, 12, 3, &mut high, &mut low);
+        assert_eq!((high, low), (0, 12345));
+
+        let data = "ab";
+        let offsets = [0, 1, 2];
+        let result = my_join_list_utf8(0, data.as_ptr().cast(), offsets.as_ptr(), 2, &mut out_len);
+        let result = unsafe { std::slice::from_raw_parts(result as *const u8, out_len as usize) };
+        assert_eq!(result, b"a,b");
+    }
+
+    #[test]
+    fn test_expanded_holder_functions() {
+        let prefix = "ab";
+        let literals = [prefix.as_ptr().cast()];
+        let literal_lens = [prefix.len() as i32];
+        let mut error_msg = std::ptr::null_mut();
+        let holder = my_has_prefix_utf8_utf8_make_holder(literals.as_ptr(), literal_lens.as_ptr(), 1, &mut error_msg);
+        assert_ne!(holder, 0);
+        assert!(error_msg.is_null());
+        my_has_prefix_utf8_utf8_free_holder(holder);
+
+        let literal_lens = [0];
+        let holder = my_has_prefix_utf8_utf8_make_holder(literals.as_ptr(), literal_lens.as_ptr(), 1, &mut error_msg);
+        assert_eq!(holder, 0);
+        let error = unsafe { std::ffi::CStr::from_ptr(error_msg) };
+        assert_eq!(error.to_str().unwrap(), "prefix must not be empty");
+        free_udf_holder_error(error_msg);
+    }
+}
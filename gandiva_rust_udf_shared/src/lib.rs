@@ -5,7 +5,7 @@ use std::ffi::CString;
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[allow(dead_code)]
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct DataType {
     pub type_name: String,
     // optional, for `list` only
@@ -16,20 +16,300 @@ pub struct DataType {
     pub scale: Option<i32>,
     // optional, for `timestamp` only
     pub unit: Option<String>,
+    // optional, for `fixed_size_binary` only
+    pub byte_width: Option<i32>,
 }
 
-impl Default for crate::DataType {
-    fn default() -> Self {
-        DataType {
-            type_name: Default::default(),
-            value_type: None,
-            precision: None,
-            scale: None,
-            unit: None,
+// a decimal value with precision `P` and scale `S`, which is stored as a 128-bit integer scaled by 10^S,
+// e.g. `Decimal128::<10, 2>::new(12345)` is 123.45
+// the `udf` macro maps it to the `decimal` type with the given precision and scale
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Decimal128<const P: i32, const S: i32>(i128);
+
+impl<const P: i32, const S: i32> Decimal128<P, S> {
+    pub const PRECISION: i32 = P;
+    pub const SCALE: i32 = S;
+
+    pub fn new(value: i128) -> Self {
+        Decimal128(value)
+    }
+
+    // the unscaled value
+    pub fn value(&self) -> i128 {
+        self.0
+    }
+
+    // gandiva passes a decimal value as its high 64 bits and low 64 bits along with its precision and scale,
+    // the value is rescaled into scale `S` rounding half up, and it is an error if the value
+    // overflows or does not fit into precision `P`
+    pub fn from_gdv_parts(high: i64, low: u64, precision: i32, scale: i32) -> Result<Self, String> {
+        let value = ((high as i128) << 64) | low as i128;
+        let rescaled = _rescale_decimal(value, scale, S)
+            .filter(|rescaled| _fits_decimal_precision(*rescaled, P))
+            .ok_or_else(|| {
+                format!(
+                    "decimal({}, {}) value {} does not fit into decimal({}, {})",
+                    precision,
+                    scale,
+                    _format_decimal(value, scale),
+                    P,
+                    S
+                )
+            })?;
+        Ok(Decimal128(rescaled))
+    }
+
+    // gandiva passes the precision and scale of the decimal result to the function,
+    // the value is rescaled from scale `S` into the result scale in the same way as `from_gdv_parts`
+    pub fn to_gdv_parts(&self, precision: i32, scale: i32) -> Result<(i64, u64), String> {
+        let value = _rescale_decimal(self.0, S, scale)
+            .filter(|value| _fits_decimal_precision(*value, precision))
+            .ok_or_else(|| format!("decimal value {} does not fit into decimal({}, {})", self, precision, scale))?;
+        Ok(((value >> 64) as i64, value as u64))
+    }
+
+    pub fn high(&self) -> i64 {
+        (self.0 >> 64) as i64
+    }
+
+    pub fn low(&self) -> u64 {
+        self.0 as u64
+    }
+}
+
+// `None` if the rescaled value overflows, the value is rounded half up (away from zero) if the scale is reduced
+fn _rescale_decimal(value: i128, from_scale: i32, to_scale: i32) -> Option<i128> {
+    match to_scale.cmp(&from_scale) {
+        std::cmp::Ordering::Equal => Some(value),
+        std::cmp::Ordering::Greater => 10i128
+            .checked_pow(to_scale.abs_diff(from_scale))
+            .and_then(|multiplier| value.checked_mul(multiplier)),
+        std::cmp::Ordering::Less => match 10i128.checked_pow(from_scale.abs_diff(to_scale)) {
+            Some(divisor) => {
+                let quotient = value / divisor;
+                let remainder = (value % divisor).unsigned_abs();
+                if remainder >= divisor.unsigned_abs() - remainder {
+                    Some(quotient + value.signum())
+                } else {
+                    Some(quotient)
+                }
+            }
+            // the divisor is larger than any value, which is rounded to zero
+            None => Some(0),
+        }
+    }
+}
+
+// whether the unscaled value has at most `precision` digits
+fn _fits_decimal_precision(value: i128, precision: i32) -> bool {
+    match 10u128.checked_pow(precision.max(0) as u32) {
+        Some(limit) => value.unsigned_abs() < limit,
+        None => true,
+    }
+}
+
+fn _format_decimal(value: i128, scale: i32) -> String {
+    let sign = if value < 0 { "-" } else { "" };
+    let value = value.unsigned_abs();
+    if scale <= 0 {
+        return format!("{}{}{}", sign, value, "0".repeat(-scale as usize));
+    }
+    match 10u128.checked_pow(scale as u32) {
+        Some(divisor) => format!(
+            "{}{}.{:0width$}",
+            sign,
+            value / divisor,
+            value % divisor,
+            width = scale as usize
+        ),
+        None => format!("{}0.{:0width$}", sign, value, width = scale as usize),
+    }
+}
+
+impl<const P: i32, const S: i32> std::fmt::Display for Decimal128<P, S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", _format_decimal(self.0, S))
+    }
+}
+
+// the unit of `Time32`, `Time64` and `Timestamp`, `NAME` is the unit registered in `DataType::unit`
+pub trait TimeUnit {
+    const NAME: &'static str;
+}
+
+// units supported by `Time32`
+pub trait Time32Unit: TimeUnit {}
+
+// units supported by `Time64`
+pub trait Time64Unit: TimeUnit {}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Second;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Millisecond;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Microsecond;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Nanosecond;
+
+impl TimeUnit for Second {
+    const NAME: &'static str = "SECOND";
+}
+
+impl TimeUnit for Millisecond {
+    const NAME: &'static str = "MILLI";
+}
+
+impl TimeUnit for Microsecond {
+    const NAME: &'static str = "MICRO";
+}
+
+impl TimeUnit for Nanosecond {
+    const NAME: &'static str = "NANO";
+}
+
+impl Time32Unit for Second {}
+impl Time32Unit for Millisecond {}
+impl Time64Unit for Microsecond {}
+impl Time64Unit for Nanosecond {}
+
+// days since the UNIX epoch, which is mapped to the `date32` type
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date32(pub i32);
+
+// milliseconds since the UNIX epoch, which is mapped to the `date64` type
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date64(pub i64);
+
+// the time of day in unit `U` (`Second` or `Millisecond`), which is mapped to the `time32` type
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time32<U: Time32Unit>(pub i32, pub std::marker::PhantomData<U>);
+
+// the time of day in unit `U` (`Microsecond` or `Nanosecond`), which is mapped to the `time64` type
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time64<U: Time64Unit>(pub i64, pub std::marker::PhantomData<U>);
+
+// the time since the UNIX epoch in unit `U`, which is mapped to the `timestamp` type
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp<U: TimeUnit>(pub i64, pub std::marker::PhantomData<U>);
+
+impl<U: Time32Unit> Time32<U> {
+    pub fn new(value: i32) -> Self {
+        Time32(value, std::marker::PhantomData)
+    }
+
+    pub fn value(&self) -> i32 {
+        self.0
+    }
+}
+
+impl<U: Time64Unit> Time64<U> {
+    pub fn new(value: i64) -> Self {
+        Time64(value, std::marker::PhantomData)
+    }
+
+    pub fn value(&self) -> i64 {
+        self.0
+    }
+}
+
+impl<U: TimeUnit> Timestamp<U> {
+    pub fn new(value: i64) -> Self {
+        Timestamp(value, std::marker::PhantomData)
+    }
+
+    pub fn value(&self) -> i64 {
+        self.0
+    }
+}
+
+// a list of strings passed by gandiva, which is mapped to the `list<utf8>` type,
+// the strings are stored in one data buffer, and the i-th string is at `data[offsets[i]..offsets[i + 1]]`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StrList<'a> {
+    data: &'a [u8],
+    offsets: &'a [i32],
+}
+
+impl<'a> StrList<'a> {
+    /// Build the list from the data buffer, the offsets (`len + 1` entries) and the number of strings,
+    /// which is used by the `udf` macro for the `utf8 = "unchecked"` policy.
+    ///
+    /// # Safety
+    /// `data` and `offsets` must be valid for the given length and outlive the returned list,
+    /// and each string must be valid UTF-8.
+    pub unsafe fn from_gdv_parts(data: *const libc::c_char, offsets: *const i32, len: i32) -> Self {
+        if len <= 0 || data.is_null() || offsets.is_null() {
+            return StrList::default();
+        }
+        let offsets = std::slice::from_raw_parts(offsets, len as usize + 1);
+        let data = std::slice::from_raw_parts(data as *const u8, offsets[len as usize] as usize);
+        StrList { data, offsets }
+    }
+
+    /// Build the list like `from_gdv_parts` and check that each string is valid UTF-8,
+    /// which is used by the `udf` macro for the `utf8 = "error"` policy.
+    ///
+    /// # Safety
+    /// `data` and `offsets` must be valid for the given length and outlive the returned list.
+    pub unsafe fn from_gdv_parts_checked(
+        data: *const libc::c_char,
+        offsets: *const i32,
+        len: i32,
+    ) -> Result<Self, String> {
+        let list = Self::from_gdv_parts(data, offsets, len);
+        for index in 0..list.len() {
+            if let Err(err) = std::str::from_utf8(list.bytes(index)) {
+                return Err(format!("string at index {}: {}", index, err));
+            }
         }
+        Ok(list)
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // return the i-th string, or None if the index is out of bounds
+    pub fn get(&self, index: usize) -> Option<&'a str> {
+        if index >= self.len() {
+            return None;
+        }
+        // the strings are checked by `from_gdv_parts_checked` or trusted by `from_gdv_parts`
+        Some(unsafe { std::str::from_utf8_unchecked(self.bytes(index)) })
+    }
+
+    fn bytes(&self, index: usize) -> &'a [u8] {
+        &self.data[self.offsets[index] as usize..self.offsets[index + 1] as usize]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'a str> + 'a {
+        let list = *self;
+        (0..list.len()).filter_map(move |index| list.get(index))
     }
 }
 
+// a custom parameter/result type of UDFs defined with `#[udf(unchecked_types = true)]`,
+// `TYPE_NAME` is the Arrow type name registered for it, e.g. "int64" for a newtype of `i64`
+// the value is passed to/returned from the C wrapper function as is, so the type must be FFI-safe,
+// e.g. a `#[repr(transparent)]` newtype of a primitive type, and implement `Default` if the UDF
+// handles NULL internally or can return errors
+pub trait GdvType {
+    const TYPE_NAME: &'static str;
+}
+
 #[allow(dead_code)]
 // implement serialized and deserialized for UdfMetaData
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -37,11 +317,51 @@ pub struct UdfMetaData {
     pub name: String,
     pub aliases: Vec<String>,
     pub param_types: Vec<DataType>,
+    // the names of the parameters, empty for libraries built with older versions
+    #[serde(default)]
+    pub param_names: Vec<String>,
     pub return_type: DataType,
     pub pc_name: String,
     pub result_nullable: String,
     pub can_return_errors: bool,
     pub needs_context: bool,
+    // the C functions making and freeing the holder of a stateful UDF, see `UdfHolder`,
+    // the holder maker is called with the literal arguments once per expression,
+    // its error message is released with the `free_udf_holder_error` C function of the library
+    #[serde(default)]
+    pub holder_maker: Option<String>,
+    #[serde(default)]
+    pub holder_free: Option<String>,
+    // whether each parameter must be a literal, empty if none of them is
+    #[serde(default)]
+    pub literal_params: Vec<bool>,
+    // the description of the function from its doc comments, and its usage examples
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub examples: Vec<String>,
+    // whether the result may change for the same arguments, "immutable", "stable" within a query or "volatile",
+    // only immutable functions can be constant-folded or cached
+    #[serde(default = "default_volatility")]
+    pub volatility: String,
+    // the deprecation message of the function and the version since which it is deprecated,
+    // and the aliases which are deprecated, which are also listed in `aliases`
+    #[serde(default)]
+    pub deprecated: Option<String>,
+    #[serde(default)]
+    pub deprecated_since: Option<String>,
+    #[serde(default)]
+    pub deprecated_aliases: Vec<String>,
+    // the namespace of the function, which hosts may expose as `namespace.name` or as extra aliases,
+    // and the category of the function for documentation
+    #[serde(default)]
+    pub namespace: Option<String>,
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+fn default_volatility() -> String {
+    "immutable".to_string()
 }
 
 impl Default for UdfMetaData {
@@ -50,11 +370,23 @@ impl Default for UdfMetaData {
             name: Default::default(),
             aliases: vec![],
             param_types: vec![],
+            param_names: vec![],
             return_type: DataType::default(),
             pc_name: Default::default(),
             result_nullable: "if_null".to_string(),
             can_return_errors: false,
             needs_context: false,
+            holder_maker: None,
+            holder_free: None,
+            literal_params: vec![],
+            description: None,
+            examples: vec![],
+            volatility: default_volatility(),
+            deprecated: None,
+            deprecated_since: None,
+            deprecated_aliases: vec![],
+            namespace: None,
+            category: None,
         }
     }
 }
@@ -81,10 +413,211 @@ pub extern "C" fn initialize_gdv_fn_context(
     }
 }
 
+// the gandiva function context, a UDF can take it as its first parameter to allocate memory
+// in the context arena or to report errors, e.g. `fn my_udf(ctx: &GdvContext, x: &str) -> ...`,
+// the context is only valid during the call of the UDF, so the memory allocated by the context
+// borrows the context and cannot outlive the current row
+#[derive(Debug)]
+pub struct GdvContext {
+    ctx: i64,
+}
+
+impl GdvContext {
+    /// Wrap the context passed to the C wrapper function, which is used by the `udf` macro.
+    ///
+    /// # Safety
+    /// `ctx` must be the context handle passed by Gandiva to the wrapper function, and the returned
+    /// context must not be used after the wrapper function returns.
+    #[doc(hidden)]
+    pub unsafe fn new(ctx: i64) -> Self {
+        GdvContext { ctx }
+    }
+
+    // the raw context handle, e.g. for `return_gdv_string`
+    pub fn handle(&self) -> i64 {
+        self.ctx
+    }
+
+    // copy the bytes into memory allocated in the context arena
+    // return None if the memory allocation fails
+    pub fn alloc_bytes(&self, bytes: &[u8]) -> Option<&[u8]> {
+        if bytes.is_empty() {
+            return Some(&[]);
+        }
+        let ptr = unsafe {
+            let context_arena_malloc = GDV_FN_CONTEXT_ARENA_MALLOC?;
+            context_arena_malloc(self.ctx, bytes.len() as i32)
+        } as *mut u8;
+        if ptr.is_null() {
+            return None;
+        }
+        unsafe {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
+            Some(std::slice::from_raw_parts(ptr, bytes.len()))
+        }
+    }
+
+    // copy the string into memory allocated in the context arena
+    // return None if the memory allocation fails
+    pub fn alloc_str(&self, s: &str) -> Option<&str> {
+        let bytes = self.alloc_bytes(s.as_bytes())?;
+        // the bytes are copied from a valid string
+        Some(unsafe { std::str::from_utf8_unchecked(bytes) })
+    }
+
+    // report the error of the current row, the function should return after reporting the error
+    pub fn set_error(&self, error_msg: &str) {
+        set_error_msg(self.ctx, &error_message("", error_msg));
+    }
+}
+
+// the string result written directly into memory allocated in the context arena, which avoids copying
+// a `String` into the arena, a UDF can take it as a parameter and return `()` or `Result<(), E>`,
+// e.g. `fn my_udf(x: &str, out: &mut GdvStringWriter) -> Result<(), std::fmt::Error> { write!(out, "{}!", x) }`
+pub struct GdvStringWriter<'a> {
+    ctx: &'a GdvContext,
+    buffer: *mut u8,
+    len: usize,
+    capacity: usize,
+    alloc_failed: bool,
+}
+
+impl<'a> GdvStringWriter<'a> {
+    // the initial capacity allocated for the first write
+    const MIN_CAPACITY: usize = 64;
+
+    pub fn new(ctx: &'a GdvContext) -> Self {
+        GdvStringWriter {
+            ctx,
+            buffer: std::ptr::null_mut(),
+            len: 0,
+            capacity: 0,
+            alloc_failed: false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // the string written so far
+    pub fn as_str(&self) -> &str {
+        if self.len == 0 {
+            return "";
+        }
+        // only strings are written into the buffer
+        unsafe { std::str::from_utf8_unchecked(std::slice::from_raw_parts(self.buffer, self.len)) }
+    }
+
+    pub fn push_str(&mut self, s: &str) -> std::fmt::Result {
+        if self.len + s.len() > self.capacity {
+            self.grow(s.len())?;
+        }
+        unsafe {
+            std::ptr::copy_nonoverlapping(s.as_ptr(), self.buffer.add(self.len), s.len());
+        }
+        self.len += s.len();
+        Ok(())
+    }
+
+    // the arena memory cannot be reallocated, so a larger buffer is allocated and the written string
+    // is copied into it, the previous buffer is released along with the context arena
+    fn grow(&mut self, additional: usize) -> std::fmt::Result {
+        let capacity = (self.len + additional)
+            .max(self.capacity * 2)
+            .max(Self::MIN_CAPACITY);
+        let buffer = match i32::try_from(capacity) {
+            Ok(size) => unsafe {
+                match GDV_FN_CONTEXT_ARENA_MALLOC {
+                    Some(context_arena_malloc) => context_arena_malloc(self.ctx.handle(), size) as *mut u8,
+                    None => std::ptr::null_mut(),
+                }
+            },
+            Err(_) => std::ptr::null_mut(),
+        };
+        if buffer.is_null() {
+            self.alloc_failed = true;
+            return Err(std::fmt::Error);
+        }
+        if self.len > 0 {
+            unsafe {
+                std::ptr::copy_nonoverlapping(self.buffer, buffer, self.len);
+            }
+        }
+        self.buffer = buffer;
+        self.capacity = capacity;
+        Ok(())
+    }
+
+    // this function is used by the `udf` macro to return the written string
+    // (returning an i32 output parameter as string length), the memory allocation failure is reported as an error
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn into_gdv_string(self, out_len: *mut i32) -> *mut libc::c_char {
+        if self.alloc_failed {
+            set_error_msg(self.ctx.handle(), "Memory allocation failed");
+            unsafe {
+                *out_len = 0;
+            }
+            return std::ptr::null_mut();
+        }
+        if self.len == 0 {
+            return return_gdv_bytes(self.ctx.handle(), &[], out_len);
+        }
+        unsafe {
+            *out_len = self.len as i32;
+        }
+        self.buffer as *mut libc::c_char
+    }
+}
+
+impl std::fmt::Write for GdvStringWriter<'_> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.push_str(s)
+    }
+}
+
 // this function is used by the `udf` macro, if the Rust UDF returns a String,
 // it will be converted into a C string (returning an i32 output parameter as string length, and libc::c_char array as data) using this function
 #[allow(dead_code)]
 pub fn return_gdv_string(ctx: i64, result: &str, out_len: *mut i32) -> *mut libc::c_char {
+    return_gdv_bytes(ctx, result.as_bytes(), out_len)
+}
+
+// this function is used by the `udf` macro, if the Rust UDF returns a borrowed `&str`, the string is returned
+// without copying when it points into one of the string/binary parameters, whose data pointers and lengths
+// are given by `inputs`, otherwise it is copied into memory allocated by gandiva context like `return_gdv_string`
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn return_gdv_borrowed_str(
+    ctx: i64,
+    result: &str,
+    inputs: &[(*const u8, i32)],
+    out_len: *mut i32,
+) -> *mut libc::c_char {
+    let start = result.as_ptr() as usize;
+    let end = start + result.len();
+    let is_borrowed = !result.is_empty()
+        && inputs.iter().any(|&(input, input_len)| {
+            let input_start = input as usize;
+            !input.is_null() && input_start <= start && end <= input_start + input_len.max(0) as usize
+        });
+    if !is_borrowed {
+        return return_gdv_string(ctx, result, out_len);
+    }
+    unsafe {
+        *out_len = result.len() as i32;
+    }
+    result.as_ptr() as *mut libc::c_char
+}
+
+// this function is used by the `udf` macro, if the Rust UDF returns binary data such as `Vec<u8>`,
+// the bytes will be copied into memory allocated by gandiva context (returning an i32 output parameter as data length)
+#[allow(dead_code)]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn return_gdv_bytes(ctx: i64, result: &[u8], out_len: *mut i32) -> *mut libc::c_char {
     let result_len = result.len() as i32;
     if result_len == 0 {
         unsafe {
@@ -114,7 +647,7 @@ pub fn return_gdv_string(ctx: i64, result: &str, out_len: *mut i32) -> *mut libc
     }
     unsafe {
         std::ptr::copy_nonoverlapping(
-            result.as_ptr() as *const u8,
+            result.as_ptr(),
             result_ptr as *mut u8,
             result_len as usize,
         );
@@ -128,13 +661,108 @@ pub fn set_error_msg(ctx: i64, error_msg: &str) {
     unsafe {
         if let Some(context_set_error_msg) = GDV_FN_CONTEXT_SET_ERROR_MSG {
             let error_message = CString::new(error_msg).expect("CString::new failed");
-            context_set_error_msg(ctx, error_message.as_ptr() as *const i8);
+            context_set_error_msg(ctx, error_message.as_ptr().cast());
         } else {
             eprintln!("GDV_FN_CONTEXT_SET_ERROR_MSG is not set");
         }
     }
 }
 
+// this function is used by the `udf` macro, a panic in the UDF is caught by the C wrapper function
+// and its message is reported through `set_error_msg`, which requires a message without NUL bytes
+pub fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    let message = if let Some(message) = panic.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.as_str()
+    } else {
+        "unknown panic"
+    };
+    message.replace('\0', "")
+}
+
+// this function is used by the `udf` macro, the error returned from the UDF is formatted through `Display`
+// with the optional prefix of `#[udf(error_prefix = "...")]`, NUL bytes are removed as in `panic_message`
+pub fn error_message<E: std::fmt::Display + ?Sized>(error_prefix: &str, err: &E) -> String {
+    format!("{}{}", error_prefix, err).replace('\0', "")
+}
+
+// the state of a stateful UDF, e.g. a compiled regex, which is made once per expression from the literal arguments
+// of the UDF and passed to the UDF as its first parameter, e.g. `#[udf(holder = RegexHolder)]`
+// on `fn regexp_like(holder: &RegexHolder, x: &str, #[udf(literal)] pattern: &str) -> bool`,
+// the literals are given as strings in the order of the literal parameters
+pub trait UdfHolder: Sized {
+    type Error: std::fmt::Display;
+
+    fn make(literals: &[&str]) -> Result<Self, Self::Error>;
+}
+
+/// Make the holder of a stateful UDF with the literal arguments and return its pointer, which is used by the `udf` macro.
+/// If it fails, 0 is returned and the error message is written into `error_msg`,
+/// which is owned by the caller and must be released with `free_udf_holder_error`.
+///
+/// # Safety
+/// `literals` and `literal_lens` must point to `literal_count` literals and their lengths in bytes,
+/// and `error_msg` must be null or valid for writes.
+pub unsafe fn make_udf_holder<H: UdfHolder>(
+    literals: *const *const libc::c_char,
+    literal_lens: *const i32,
+    literal_count: i32,
+    error_msg: *mut *mut libc::c_char,
+) -> i64 {
+    let made = std::panic::catch_unwind(|| {
+        let mut literal_values = Vec::with_capacity(literal_count.max(0) as usize);
+        for i in 0..literal_count.max(0) as usize {
+            let bytes = unsafe {
+                std::slice::from_raw_parts(*literals.add(i) as *const u8, *literal_lens.add(i) as usize)
+            };
+            let literal = std::str::from_utf8(bytes)
+                .map_err(|err| format!("Invalid UTF-8 in literal {}: {}", i, err))?;
+            literal_values.push(literal);
+        }
+        H::make(&literal_values).map_err(|err| error_message("", &err))
+    })
+    .unwrap_or_else(|panic| Err(panic_message(&*panic)));
+    match made {
+        Ok(holder) => Box::into_raw(Box::new(holder)) as i64,
+        Err(err) => {
+            if !error_msg.is_null() {
+                // the message has no NUL bytes, see `error_message` and `panic_message`
+                *error_msg = CString::new(err).unwrap_or_default().into_raw();
+            }
+            0
+        }
+    }
+}
+
+/// Release the error message written by `make_udf_holder`, which is used by the `udf_registry` macro.
+///
+/// # Safety
+/// `error_msg` must be null or written by `make_udf_holder` and not released yet.
+pub unsafe fn free_udf_holder_error(error_msg: *mut libc::c_char) {
+    if !error_msg.is_null() {
+        drop(CString::from_raw(error_msg));
+    }
+}
+
+/// Release the holder made by `make_udf_holder`, which is used by the `udf` macro.
+///
+/// # Safety
+/// `holder` must be 0 or made by `make_udf_holder::<H>` and not freed yet, it must not be used after being freed.
+pub unsafe fn free_udf_holder<H: UdfHolder>(holder: i64) {
+    if holder != 0 {
+        drop(Box::from_raw(holder as *mut H));
+    }
+}
+
+/// Pass the holder made by `make_udf_holder` to the UDF, which is used by the `udf` macro.
+///
+/// # Safety
+/// `holder` must be made by `make_udf_holder::<H>` and not freed yet.
+pub unsafe fn udf_holder<'a, H: UdfHolder>(holder: i64) -> &'a H {
+    &*(holder as *const H)
+}
+
 // the #udf macro will generate a function for registration along with the C wrapper function
 // the function for registration will register the function metadata into UDF_REGISTRY
 // all UDFs' metadata is stored in the UDF_REGISTRY, which will be marshalled into JSON and read
@@ -148,6 +776,37 @@ pub fn register_udf(udf_meta_data: UdfMetaData) {
     udf_registry.push(udf_meta_data);
 }
 
+// this function is used by the `udf_registry` macro, the namespace and category specified by
+// `#[udf_registry(namespace = "net", category = "string")]` are the defaults of the UDFs not specifying them
+pub fn apply_udf_registry_defaults(namespace: Option<&str>, category: Option<&str>) {
+    let mut udf_registry = UDF_REGISTRY.lock().unwrap();
+    _apply_udf_defaults(udf_registry.iter_mut(), namespace, category);
+}
+
+// this function is used by the generated UDF registry, the namespace and category specified by
+// `[package.metadata.gandiva_udf]` of a package are the defaults of its UDFs registered by `register`
+pub fn register_package_udfs(namespace: Option<&str>, category: Option<&str>, register: impl FnOnce()) {
+    let start = UDF_REGISTRY.lock().unwrap().len();
+    register();
+    let mut udf_registry = UDF_REGISTRY.lock().unwrap();
+    _apply_udf_defaults(udf_registry.iter_mut().skip(start), namespace, category);
+}
+
+fn _apply_udf_defaults<'a>(
+    udfs: impl Iterator<Item = &'a mut UdfMetaData>,
+    namespace: Option<&str>,
+    category: Option<&str>,
+) {
+    for udf_meta_data in udfs {
+        if udf_meta_data.namespace.is_none() {
+            udf_meta_data.namespace = namespace.map(str::to_string);
+        }
+        if udf_meta_data.category.is_none() {
+            udf_meta_data.category = category.map(str::to_string);
+        }
+    }
+}
+
 // return serialized json string of UdfMetaData list, no need to use gandiva's malloc
 // this generates a function to retrieve the udf metadata as a json string
 // caller of this API should free the memory
@@ -160,10 +819,10 @@ pub extern "C" fn get_udf_registry() -> *mut libc::c_char {
     };
     let registry_json = serde_json::to_string(&udf_registry).unwrap();
     let c_str = CString::new(registry_json).unwrap();
-    let ptr = c_str.into_raw();
-    ptr
+    c_str.into_raw()
 }
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub extern "C" fn free_udf_registry(ptr: *mut libc::c_char) {
     unsafe {
         let _ = CString::from_raw(ptr);
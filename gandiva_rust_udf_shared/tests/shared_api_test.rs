@@ -1,16 +1,19 @@
 #[cfg(test)]
 mod tests {
     use gandiva_rust_udf_shared::{
-        free_udf_registry, get_udf_registry, initialize_gdv_fn_context, register_udf,
-        return_gdv_string, DataType, UdfMetaData, UdfRegistry, UDF_REGISTRY,
+        apply_udf_registry_defaults, error_message, free_udf_holder, free_udf_holder_error,
+        free_udf_registry, get_udf_registry, initialize_gdv_fn_context, make_udf_holder,
+        panic_message, register_package_udfs, register_udf, return_gdv_borrowed_str,
+        return_gdv_bytes, return_gdv_string, DataType, Decimal128, GdvContext, GdvStringWriter,
+        Millisecond, Second, StrList, Time32, TimeUnit, Timestamp, UdfHolder, UdfMetaData,
+        UdfRegistry, UDF_REGISTRY,
     };
 
-    // function used for unit testing purpose
+    // function used for unit testing purpose, the buffer is zeroed and has one extra byte
+    // so that the returned string is always nul-terminated and can be read back as a CString
     extern "C" fn gdv_fn_context_arena_malloc(_context: i64, size: i32) -> *mut i8 {
-        let mut buffer = Vec::with_capacity(size as usize);
-        let ptr = buffer.as_mut_ptr();
-        std::mem::forget(buffer);
-        ptr as *mut i8
+        let buffer = vec![0u8; size as usize + 1].into_boxed_slice();
+        Box::into_raw(buffer).cast()
     }
 
     // function used for unit testing purpose
@@ -41,7 +44,7 @@ mod tests {
         let udf_meta_data = _get_udf_meta();
         register_udf(udf_meta_data);
         let udfs = UDF_REGISTRY.lock().unwrap();
-        assert!(udfs.len() >= 1);
+        assert!(!udfs.is_empty());
         assert_eq!(udfs[0].name, "my_udf");
     }
 
@@ -54,11 +57,87 @@ mod tests {
             let registry = std::ffi::CString::from_raw(registry_c_str);
             let registry_str = registry.to_str().unwrap();
             let udf_registry: UdfRegistry = serde_json::from_str(registry_str).unwrap();
-            assert!(udf_registry.functions.len() >= 1);
+            assert!(!udf_registry.functions.is_empty());
             assert_eq!(udf_registry.functions[0].name, "my_udf");
         }
     }
 
+    #[test]
+    fn test_deserialize_udf_meta_without_optional_fields() {
+        // the registry of libraries built with older versions has no description and examples
+        let udf_meta_json = r#"{"name": "my_udf", "aliases": [], "param_types": [], "return_type": {"type_name": "int64"},
+            "pc_name": "my_udf_int64", "result_nullable": "if_null", "can_return_errors": false, "needs_context": false}"#;
+        let udf_meta: UdfMetaData = serde_json::from_str(udf_meta_json).unwrap();
+        assert!(udf_meta.param_names.is_empty());
+        assert_eq!(udf_meta.description, None);
+        assert!(udf_meta.examples.is_empty());
+        assert_eq!(udf_meta.volatility, "immutable");
+        assert_eq!(udf_meta.deprecated, None);
+        assert!(udf_meta.deprecated_aliases.is_empty());
+
+        let udf_meta = UdfMetaData {
+            description: Some("Adds one to the value.".to_string()),
+            examples: vec!["my_udf(1) => 2".to_string()],
+            ..udf_meta
+        };
+        let udf_meta: UdfMetaData = serde_json::from_str(&serde_json::to_string(&udf_meta).unwrap()).unwrap();
+        assert_eq!(udf_meta.description.as_deref(), Some("Adds one to the value."));
+        assert_eq!(udf_meta.examples, vec!["my_udf(1) => 2"]);
+    }
+
+    #[test]
+    fn test_apply_udf_registry_defaults() {
+        {
+            // the registry is shared by the tests, which expect "my_udf" to be registered first
+            let mut udfs = UDF_REGISTRY.lock().unwrap();
+            if udfs.is_empty() {
+                udfs.push(_get_udf_meta());
+            }
+            udfs.push(UdfMetaData { name: "my_net_udf".to_string(), ..Default::default() });
+            udfs.push(UdfMetaData {
+                name: "my_ip_udf".to_string(),
+                namespace: Some("ip".to_string()),
+                ..Default::default()
+            });
+        }
+        apply_udf_registry_defaults(Some("net"), Some("network"));
+        let udfs = UDF_REGISTRY.lock().unwrap();
+        let net_udf = udfs.iter().find(|udf| udf.name == "my_net_udf").unwrap();
+        assert_eq!(net_udf.namespace.as_deref(), Some("net"));
+        assert_eq!(net_udf.category.as_deref(), Some("network"));
+        let ip_udf = udfs.iter().find(|udf| udf.name == "my_ip_udf").unwrap();
+        assert_eq!(ip_udf.namespace.as_deref(), Some("ip"));
+        assert_eq!(ip_udf.category.as_deref(), Some("network"));
+    }
+
+    #[test]
+    fn test_register_package_udfs() {
+        {
+            // the registry is shared by the tests, which expect "my_udf" to be registered first
+            let mut udfs = UDF_REGISTRY.lock().unwrap();
+            if udfs.is_empty() {
+                udfs.push(_get_udf_meta());
+            }
+        }
+        // the package defaults are the same as the registry defaults applied by the other test,
+        // so that the result does not depend on the order of the tests
+        register_package_udfs(Some("net"), Some("network"), || {
+            register_udf(UdfMetaData { name: "my_package_udf".to_string(), ..Default::default() });
+            register_udf(UdfMetaData {
+                name: "my_package_ip_udf".to_string(),
+                namespace: Some("ip".to_string()),
+                ..Default::default()
+            });
+        });
+        let udfs = UDF_REGISTRY.lock().unwrap();
+        let package_udf = udfs.iter().find(|udf| udf.name == "my_package_udf").unwrap();
+        assert_eq!(package_udf.namespace.as_deref(), Some("net"));
+        assert_eq!(package_udf.category.as_deref(), Some("network"));
+        let ip_udf = udfs.iter().find(|udf| udf.name == "my_package_ip_udf").unwrap();
+        assert_eq!(ip_udf.namespace.as_deref(), Some("ip"));
+        assert_eq!(ip_udf.category.as_deref(), Some("network"));
+    }
+
     #[test]
     fn test_free_udf_registry() {
         let udf_meta_data = _get_udf_meta();
@@ -92,4 +171,189 @@ mod tests {
             assert_eq!(out_length, 0);
         }
     }
+
+    #[test]
+    fn test_return_gdv_bytes() {
+        initialize_gdv_fn_context(gdv_fn_context_arena_malloc, gdv_fn_context_set_error_msg);
+        // binary data may contain zero bytes, so it is read back using the output length
+        let mut out_length = 0;
+        let data = [0xde, 0x00, 0xbe, 0xef];
+        let result = return_gdv_bytes(0, &data, &mut out_length);
+        assert_eq!(out_length, 4);
+        let result_bytes =
+            unsafe { std::slice::from_raw_parts(result as *const u8, out_length as usize) };
+        assert_eq!(result_bytes, &data);
+    }
+
+    #[test]
+    fn test_gdv_context_alloc() {
+        initialize_gdv_fn_context(gdv_fn_context_arena_malloc, gdv_fn_context_set_error_msg);
+        let ctx = unsafe { GdvContext::new(0) };
+        assert_eq!(ctx.handle(), 0);
+        assert_eq!(ctx.alloc_str("hello"), Some("hello"));
+        assert_eq!(ctx.alloc_str(""), Some(""));
+        assert_eq!(ctx.alloc_bytes(&[0xde, 0x00, 0xbe, 0xef]), Some(&[0xde, 0x00, 0xbe, 0xef][..]));
+    }
+
+    #[test]
+    fn test_gdv_string_writer() {
+        use std::fmt::Write;
+        initialize_gdv_fn_context(gdv_fn_context_arena_malloc, gdv_fn_context_set_error_msg);
+        let ctx = unsafe { GdvContext::new(0) };
+        let mut writer = GdvStringWriter::new(&ctx);
+        assert!(writer.is_empty());
+        // the buffer grows while keeping the written string
+        for i in 0..20 {
+            write!(writer, "{},", i).unwrap();
+        }
+        let expected: String = (0..20).map(|i| format!("{},", i)).collect();
+        assert_eq!(writer.as_str(), expected);
+        let mut out_length = 0;
+        let result = writer.into_gdv_string(&mut out_length);
+        assert_eq!(out_length as usize, expected.len());
+        let result_bytes =
+            unsafe { std::slice::from_raw_parts(result as *const u8, out_length as usize) };
+        assert_eq!(result_bytes, expected.as_bytes());
+    }
+
+    #[test]
+    fn test_return_gdv_borrowed_str() {
+        initialize_gdv_fn_context(gdv_fn_context_arena_malloc, gdv_fn_context_set_error_msg);
+        let input = "hello world";
+        let inputs = [(input.as_ptr(), input.len() as i32)];
+        // a slice of the input is returned as is
+        let mut out_length = 0;
+        let result = return_gdv_borrowed_str(0, &input[6..], &inputs, &mut out_length);
+        assert_eq!(result as *const u8, input[6..].as_ptr());
+        assert_eq!(out_length, 5);
+        // other strings are copied
+        let other = String::from("world");
+        let result = return_gdv_borrowed_str(0, &other, &inputs, &mut out_length);
+        assert_ne!(result as *const u8, other.as_ptr());
+        let result_bytes =
+            unsafe { std::slice::from_raw_parts(result as *const u8, out_length as usize) };
+        assert_eq!(result_bytes, b"world");
+    }
+
+    struct PrefixHolder {
+        prefix: String,
+    }
+
+    impl UdfHolder for PrefixHolder {
+        type Error = String;
+
+        fn make(literals: &[&str]) -> Result<Self, Self::Error> {
+            match literals {
+                [prefix] if !prefix.is_empty() => Ok(PrefixHolder { prefix: prefix.to_string() }),
+                _ => Err("prefix must not be empty".to_string()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_make_and_free_udf_holder() {
+        let literal = "foo";
+        let literals = [literal.as_ptr().cast()];
+        let literal_lens = [literal.len() as i32];
+        let mut error_msg = std::ptr::null_mut();
+        let holder =
+            unsafe { make_udf_holder::<PrefixHolder>(literals.as_ptr(), literal_lens.as_ptr(), 1, &mut error_msg) };
+        assert_ne!(holder, 0);
+        assert!(error_msg.is_null());
+        assert_eq!(unsafe { gandiva_rust_udf_shared::udf_holder::<PrefixHolder>(holder) }.prefix, "foo");
+        unsafe { free_udf_holder::<PrefixHolder>(holder) };
+
+        let literal_lens = [0];
+        let holder =
+            unsafe { make_udf_holder::<PrefixHolder>(literals.as_ptr(), literal_lens.as_ptr(), 1, &mut error_msg) };
+        assert_eq!(holder, 0);
+        let error = unsafe { std::ffi::CStr::from_ptr(error_msg) };
+        assert_eq!(error.to_str().unwrap(), "prefix must not be empty");
+        unsafe { free_udf_holder_error(error_msg) };
+    }
+
+    #[test]
+    fn test_decimal128_gdv_parts() {
+        let decimal = Decimal128::<10, 2>::new(-12345);
+        assert_eq!(decimal.to_string(), "-123.45");
+        let same_scale = Decimal128::<10, 2>::from_gdv_parts(decimal.high(), decimal.low(), 10, 2).unwrap();
+        assert_eq!(same_scale, decimal);
+        // the value is rescaled into the scale of the type
+        let larger_scale = Decimal128::<12, 4>::from_gdv_parts(decimal.high(), decimal.low(), 10, 2).unwrap();
+        assert_eq!(larger_scale.value(), -1234500);
+        // reducing the scale rounds half up
+        let smaller_scale = Decimal128::<10, 1>::from_gdv_parts(decimal.high(), decimal.low(), 10, 2).unwrap();
+        assert_eq!(smaller_scale.to_string(), "-123.5");
+        let rounded_down = Decimal128::<10, 1>::from_gdv_parts(0, 12344, 10, 2).unwrap();
+        assert_eq!(rounded_down.to_string(), "123.4");
+        // a value not fitting into the precision of the type is an error
+        assert_eq!(
+            Decimal128::<4, 2>::from_gdv_parts(decimal.high(), decimal.low(), 10, 2).unwrap_err(),
+            "decimal(10, 2) value -123.45 does not fit into decimal(4, 2)"
+        );
+        // rescaling overflow is an error instead of wrapping
+        let max = Decimal128::<38, 0>::new(i128::MAX / 2);
+        assert!(Decimal128::<38, 10>::from_gdv_parts(max.high(), max.low(), 38, 0).is_err());
+        // the result is rescaled into the precision and scale expected by gandiva
+        assert_eq!(decimal.to_gdv_parts(12, 4).unwrap(), (-1, (-1234500i128) as u64));
+        assert_eq!(decimal.to_gdv_parts(10, 0).unwrap(), (-1, (-123i128) as u64));
+        assert_eq!(
+            decimal.to_gdv_parts(3, 2).unwrap_err(),
+            "decimal value -123.45 does not fit into decimal(3, 2)"
+        );
+        assert_eq!(Decimal128::<5, 0>::new(7).to_string(), "7");
+    }
+
+    #[test]
+    fn test_temporal_types() {
+        // date/time types are transparent over their underlying integers
+        assert_eq!(std::mem::size_of::<Timestamp<Millisecond>>(), std::mem::size_of::<i64>());
+        assert_eq!(std::mem::size_of::<Time32<Second>>(), std::mem::size_of::<i32>());
+        assert_eq!(Timestamp::<Millisecond>::new(42).value(), 42);
+        assert_eq!(Time32::<Second>::new(7).value(), 7);
+        assert_eq!(Millisecond::NAME, "MILLI");
+    }
+
+    #[test]
+    fn test_str_list() {
+        let data = "foobarbaz";
+        let offsets = [0, 3, 3, 9];
+        let list = unsafe { StrList::from_gdv_parts(data.as_ptr().cast(), offsets.as_ptr(), 3) };
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.get(1), Some(""));
+        assert_eq!(list.get(3), None);
+        assert_eq!(list.iter().collect::<Vec<_>>(), vec!["foo", "", "barbaz"]);
+
+        let empty = unsafe { StrList::from_gdv_parts(std::ptr::null(), std::ptr::null(), 0) };
+        assert!(empty.is_empty());
+
+        // each string is checked, so a character split between two strings is invalid
+        let checked = unsafe { StrList::from_gdv_parts_checked(data.as_ptr().cast(), offsets.as_ptr(), 3) };
+        assert_eq!(checked.unwrap().iter().collect::<Vec<_>>(), vec!["foo", "", "barbaz"]);
+        let data = "aé".as_bytes();
+        let offsets = [0, 2, 3];
+        let invalid = unsafe { StrList::from_gdv_parts_checked(data.as_ptr().cast(), offsets.as_ptr(), 2) };
+        assert_eq!(
+            invalid.unwrap_err(),
+            "string at index 0: incomplete utf-8 byte sequence from index 1"
+        );
+    }
+
+    #[test]
+    fn test_panic_message() {
+        let panic = std::panic::catch_unwind(|| panic!("static message")).unwrap_err();
+        assert_eq!(panic_message(&*panic), "static message");
+        let panic = std::panic::catch_unwind(|| panic!("formatted {}\0message", 42)).unwrap_err();
+        assert_eq!(panic_message(&*panic), "formatted 42message");
+        let panic = std::panic::catch_unwind(|| std::panic::panic_any(42)).unwrap_err();
+        assert_eq!(panic_message(&*panic), "unknown panic");
+    }
+
+    #[test]
+    fn test_error_message() {
+        assert_eq!(error_message("", "plain error"), "plain error");
+        assert_eq!(error_message("my_udf: ", &42), "my_udf: 42");
+        let err: Box<dyn std::error::Error> = "boxed\0error".into();
+        assert_eq!(error_message("my_udf: ", &err), "my_udf: boxederror");
+    }
 }